@@ -30,6 +30,11 @@ fn main() {
         }
         println!("cargo:include={}", d.to_string_lossy());
     }
+    // the statistics feature refers to the dictionaries of the data directory by absolute paths
+    if let Some(data_dir) = find_opencc_data_dir() {
+        println!("cargo:rustc-env=OPENCC_RUST_DATA_DIR={}", data_dir.to_string_lossy());
+    }
+
     println!("cargo:rerun-if-env-changed=OPENCC_LIBS");
 
     let target = env::var("TARGET").unwrap();
@@ -108,6 +113,29 @@ fn find_opencc_dir() -> Result<PathBuf, env::VarError> {
     env::var("OPENCC_DIR").map(PathBuf::from)
 }
 
+fn find_opencc_data_dir() -> Option<PathBuf> {
+    println!("cargo:rerun-if-env-changed=OPENCC_DATA_DIR");
+
+    if let Ok(data_dir) = env::var("OPENCC_DATA_DIR") {
+        return Some(PathBuf::from(data_dir));
+    }
+
+    if let Ok(dir) = find_opencc_dir() {
+        return Some(dir.join("share").join("opencc"));
+    }
+
+    let output = Command::new("pkg-config").arg("--variable=prefix").arg("opencc").output().ok()?;
+
+    let prefix = String::from_utf8(output.stdout).ok()?;
+    let prefix = prefix.trim();
+
+    if !output.status.success() || prefix.is_empty() {
+        return None;
+    }
+
+    Some(PathBuf::from(prefix).join("share").join("opencc"))
+}
+
 fn determine_mode<T: AsRef<str>>(libdirs: &[PathBuf], libs: &[T]) -> &'static str {
     println!("cargo:rerun-if-env-changed=OPENCC_STATIC");
     let kind = env::var("OPENCC_STATIC").ok();
@@ -0,0 +1,126 @@
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, BufReader, BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+static TEMPORARY_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Create a new temporary file next to `output`.
+fn create_temporary(output: &Path) -> io::Result<(PathBuf, File)> {
+    let file_name = output.file_name().map(|name| name.to_string_lossy()).unwrap_or_default();
+
+    loop {
+        let path = output.with_file_name(format!(
+            ".{}.opencc-rust-{}-{}",
+            file_name,
+            process::id(),
+            TEMPORARY_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Rewrite a zip archive entry by entry.
+///
+/// `convert` gets the name and the content of each UTF-8 entry, and returns the new content, or `None` to copy the entry as it is (without recompressing it). An entry named `mimetype` is written first and stored without compression, as EPUB and OpenDocument require.
+///
+/// The new archive is written to a temporary file next to `output`, which replaces `output` when it is complete, so `output` can be `input` itself.
+pub(crate) fn convert_archive<F>(input: &Path, output: &Path, convert: F) -> io::Result<()>
+where
+    F: FnMut(&str, &str) -> Option<String>, {
+    let archive = ZipArchive::new(BufReader::new(File::open(input)?))?;
+
+    let (temporary, file) = create_temporary(output)?;
+
+    // the input is closed by `write_archive` before it may be replaced
+    let result =
+        write_archive(archive, file, convert).and_then(|()| fs::rename(&temporary, output));
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temporary);
+    }
+
+    result
+}
+
+fn write_archive<R: Read + io::Seek, F>(
+    mut archive: ZipArchive<R>,
+    file: File,
+    mut convert: F,
+) -> io::Result<()>
+where
+    F: FnMut(&str, &str) -> Option<String>, {
+    let mut writer = ZipWriter::new(BufWriter::new(file));
+
+    let mut indices: Vec<usize> = (0..archive.len()).collect();
+
+    if let Some(index) = archive.index_for_name("mimetype") {
+        indices.retain(|&i| i != index);
+        indices.insert(0, index);
+    }
+
+    for index in indices {
+        let entry = {
+            let mut file = archive.by_index(index)?;
+
+            if file.is_dir() {
+                None
+            } else {
+                let mut options =
+                    SimpleFileOptions::default().compression_method(file.compression());
+
+                if let Some(time) = file.last_modified() {
+                    options = options.last_modified_time(time);
+                }
+
+                if let Some(mode) = file.unix_mode() {
+                    options = options.unix_permissions(mode);
+                }
+
+                let mut content = Vec::with_capacity(file.size() as usize);
+
+                file.read_to_end(&mut content)?;
+
+                Some((file.name().to_string(), options, content))
+            }
+        };
+
+        let (name, mut options, content) = match entry {
+            Some(entry) => entry,
+            None => {
+                writer.raw_copy_file(archive.by_index_raw(index)?)?;
+
+                continue;
+            },
+        };
+
+        if name == "mimetype" {
+            options = options.compression_method(CompressionMethod::Stored);
+
+            writer.start_file(name, options)?;
+            writer.write_all(&content)?;
+
+            continue;
+        }
+
+        match std::str::from_utf8(&content).ok().and_then(|text| convert(&name, text)) {
+            Some(converted) => {
+                writer.start_file(name, options)?;
+                writer.write_all(converted.as_bytes())?;
+            },
+            None => writer.raw_copy_file(archive.by_index_raw(index)?)?,
+        }
+    }
+
+    writer.finish()?.flush()
+}
@@ -0,0 +1,481 @@
+use std::{
+    borrow::Cow,
+    future::Future,
+    io,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use tokio::{
+    io::{AsyncRead, AsyncWrite, ReadBuf},
+    task::{spawn_blocking, JoinError, JoinHandle},
+};
+
+use crate::{punctuation::PunctuationState, segments::last_boundary, OpenCC};
+
+/// The size (in bytes) of the text which is collected before a conversion starts at a boundary.
+const PIECE_SIZE: usize = 8 * 1024;
+
+/// The size (in bytes) of the text which is converted even without a boundary, so that the buffer does not grow without limit.
+const MAX_PIECE_SIZE: usize = 1024 * 1024;
+
+/// Get the valid UTF-8 prefix of `input`. An incomplete character at the end is left out if `allow_incomplete` is set.
+fn valid_prefix(input: &[u8], allow_incomplete: bool) -> io::Result<&str> {
+    match std::str::from_utf8(input) {
+        Ok(text) => Ok(text),
+        Err(error) if allow_incomplete && error.error_len().is_none() => {
+            Ok(std::str::from_utf8(&input[..error.valid_up_to()]).unwrap())
+        },
+        Err(_) => Err(io::Error::new(io::ErrorKind::InvalidData, "The stream is not valid UTF-8.")),
+    }
+}
+
+/// Find how much of the buffered text can be converted before more text comes.
+fn piece_end(text: &str) -> Option<usize> {
+    if text.len() < PIECE_SIZE {
+        None
+    } else if text.len() >= MAX_PIECE_SIZE {
+        Some(last_boundary(text).unwrap_or(text.len()))
+    } else {
+        last_boundary(text)
+    }
+}
+
+#[inline]
+fn join_error(error: JoinError) -> io::Error {
+    io::Error::other(error)
+}
+
+/// The state of the stages (see `OpenCC::set_punctuation` and `OpenCC::set_width_normalization`) of a text which is converted piece by piece, so that the pieces are converted like the whole text.
+///
+/// The stages look at the characters around each character, so each of them holds back the end of a piece until it sees the next piece.
+#[derive(Debug, Default)]
+struct StageState {
+    width_held:       String,
+    width_previous:   Option<char>,
+    punctuation_held: String,
+    punctuation:      PunctuationState,
+}
+
+impl StageState {
+    #[inline]
+    fn has_held(&self) -> bool {
+        !self.width_held.is_empty() || !self.punctuation_held.is_empty()
+    }
+}
+
+/// Run a stage over the text held back from the previous piece and a new piece. Unless the piece is the last one, its last character (and a half-width katakana before it, which can combine with it) is held back, and given to the stage as the next character.
+fn run_stage<F: FnOnce(&str, Option<char>) -> String>(
+    held: &mut String,
+    piece: &str,
+    is_last: bool,
+    stage: F,
+) -> String {
+    held.push_str(piece);
+
+    let text = std::mem::take(held);
+
+    let mut end = text.len();
+
+    if !is_last {
+        let mut chars = text.char_indices().rev();
+
+        if let Some((last, _)) = chars.next() {
+            end = last;
+
+            if let Some((index, '\u{FF61}'..='\u{FF9F}')) = chars.next() {
+                end = index;
+            }
+        }
+    }
+
+    let (text, tail) = text.split_at(end);
+
+    *held = tail.to_string();
+
+    stage(text, tail.chars().next())
+}
+
+impl OpenCC {
+    /// Convert a piece of a text, continuing from the state after the previous pieces. The stages hold back the end of the piece unless it is the last one.
+    fn convert_piece(&self, piece: &str, state: &mut StageState, is_last: bool) -> String {
+        if !self.has_stages() {
+            return self.convert_with_dictionaries(piece);
+        }
+
+        // the pieces end at line breaks or sentence-ending punctuation marks, which the Unicode normalization does not combine with anything
+        #[cfg(feature = "unicode-normalization")]
+        let piece = match self.unicode.as_ref() {
+            Some(unicode) => Cow::Owned(unicode.apply(piece)),
+            None => Cow::Borrowed(piece),
+        };
+
+        #[cfg(not(feature = "unicode-normalization"))]
+        let piece = Cow::Borrowed(piece);
+
+        let StageState {
+            width_held,
+            width_previous,
+            punctuation_held,
+            punctuation,
+        } = state;
+
+        let mut width_stage = |text: &str, is_last: bool| match self.width.as_ref() {
+            Some(width) => run_stage(width_held, text, is_last, |text, next| {
+                let output = width.apply_piece(text, *width_previous, next);
+
+                if let Some(last) = text.chars().next_back() {
+                    *width_previous = Some(last);
+                }
+
+                output
+            }),
+            None => text.to_string(),
+        };
+
+        let before_conversion =
+            self.width.as_ref().map(|width| width.before_conversion).unwrap_or(false);
+
+        let converted = if before_conversion {
+            self.convert_with_dictionaries(&width_stage(&piece, is_last))
+        } else {
+            self.convert_with_dictionaries(&piece)
+        };
+
+        let converted = match self.punctuation.as_ref() {
+            Some(mapping) => run_stage(punctuation_held, &converted, is_last, |text, next| {
+                mapping.apply_piece(text, punctuation, next)
+            }),
+            None => converted,
+        };
+
+        if before_conversion {
+            converted
+        } else {
+            width_stage(&converted, is_last)
+        }
+    }
+}
+
+/// Convert a piece of the buffer on the blocking threads of tokio. The state of the stages is moved to the conversion and returned with its result.
+fn spawn_conversion(
+    opencc: &Arc<OpenCC>,
+    buffer: &mut Vec<u8>,
+    end: usize,
+    state: &mut StageState,
+    is_last: bool,
+) -> JoinHandle<(String, StageState)> {
+    let opencc = opencc.clone();
+    let text = String::from_utf8(buffer.drain(..end).collect()).unwrap();
+    let mut state = std::mem::take(state);
+
+    spawn_blocking(move || {
+        let converted = opencc.convert_piece(&text, &mut state, is_last);
+
+        (converted, state)
+    })
+}
+
+/// An `AsyncRead` which converts the UTF-8 text read from another `AsyncRead`.
+///
+/// The text is collected until a line break or a sentence-ending punctuation mark, so no phrase is split, and converted with `spawn_blocking`, so the runtime is not blocked. The punctuation and width normalization stages carry their state from piece to piece, so the text is converted like `OpenCC::convert` converts it as a whole.
+pub struct ConvertAsyncReader<R> {
+    opencc:     Arc<OpenCC>,
+    inner:      R,
+    input:      Vec<u8>,
+    output:     Vec<u8>,
+    position:   usize,
+    eof:        bool,
+    stages:     StageState,
+    converting: Option<JoinHandle<(String, StageState)>>,
+}
+
+impl<R: AsyncRead + Unpin> ConvertAsyncReader<R> {
+    /// Create a reader which converts the text read from `inner` with `opencc`.
+    #[inline]
+    pub fn new(opencc: Arc<OpenCC>, inner: R) -> ConvertAsyncReader<R> {
+        ConvertAsyncReader {
+            opencc,
+            inner,
+            input: Vec::new(),
+            output: Vec::new(),
+            position: 0,
+            eof: false,
+            stages: StageState::default(),
+            converting: None,
+        }
+    }
+
+    /// Get a reference to the inner reader.
+    #[inline]
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Unwrap the inner reader. The text which is read but not converted yet is lost.
+    #[inline]
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for ConvertAsyncReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+
+        loop {
+            if this.position < this.output.len() {
+                let length = buf.remaining().min(this.output.len() - this.position);
+
+                buf.put_slice(&this.output[this.position..(this.position + length)]);
+
+                this.position += length;
+
+                return Poll::Ready(Ok(()));
+            }
+
+            if let Some(converting) = this.converting.as_mut() {
+                let (converted, stages) = match Pin::new(converting).poll(cx) {
+                    Poll::Ready(result) => result.map_err(join_error)?,
+                    Poll::Pending => return Poll::Pending,
+                };
+
+                this.converting = None;
+                this.stages = stages;
+                this.output = converted.into_bytes();
+                this.position = 0;
+
+                continue;
+            }
+
+            if this.eof {
+                if this.input.is_empty() && !this.stages.has_held() {
+                    return Poll::Ready(Ok(()));
+                }
+
+                let end = valid_prefix(&this.input, false)?.len();
+
+                this.converting = Some(spawn_conversion(
+                    &this.opencc,
+                    &mut this.input,
+                    end,
+                    &mut this.stages,
+                    true,
+                ));
+
+                continue;
+            }
+
+            if let Some(end) = piece_end(valid_prefix(&this.input, true)?) {
+                this.converting = Some(spawn_conversion(
+                    &this.opencc,
+                    &mut this.input,
+                    end,
+                    &mut this.stages,
+                    false,
+                ));
+
+                continue;
+            }
+
+            let mut chunk = [0; 8192];
+            let mut chunk = ReadBuf::new(&mut chunk);
+
+            match Pin::new(&mut this.inner).poll_read(cx, &mut chunk) {
+                Poll::Ready(Ok(())) => (),
+                Poll::Ready(Err(error)) => return Poll::Ready(Err(error)),
+                Poll::Pending => return Poll::Pending,
+            }
+
+            if chunk.filled().is_empty() {
+                this.eof = true;
+            } else {
+                this.input.extend_from_slice(chunk.filled());
+            }
+        }
+    }
+}
+
+enum WriterState {
+    Idle,
+    Converting(JoinHandle<(String, StageState)>),
+    Writing { output: Vec<u8>, written: usize },
+}
+
+/// An `AsyncWrite` which converts UTF-8 text and writes it to another `AsyncWrite`.
+///
+/// The text is collected until a line break or a sentence-ending punctuation mark, so no phrase is split, and converted with `spawn_blocking`, so the runtime is not blocked. The punctuation and width normalization stages carry their state from piece to piece, like in `ConvertAsyncReader`. `flush` converts and writes all the collected text (so the stages cannot look past it), and `shutdown` also fails if the text ends with an incomplete character.
+pub struct ConvertAsyncWriter<W> {
+    opencc:  Arc<OpenCC>,
+    inner:   W,
+    pending: Vec<u8>,
+    stages:  StageState,
+    state:   WriterState,
+}
+
+impl<W: AsyncWrite + Unpin> ConvertAsyncWriter<W> {
+    /// Create a writer which converts text with `opencc` and writes it to `inner`.
+    #[inline]
+    pub fn new(opencc: Arc<OpenCC>, inner: W) -> ConvertAsyncWriter<W> {
+        ConvertAsyncWriter {
+            opencc,
+            inner,
+            pending: Vec::new(),
+            stages: StageState::default(),
+            state: WriterState::Idle,
+        }
+    }
+
+    /// Get a reference to the inner writer.
+    #[inline]
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Unwrap the inner writer. The text which is not flushed yet is lost.
+    #[inline]
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    /// Finish the conversion in progress and write its result.
+    fn poll_drain(&mut self, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        loop {
+            match &mut self.state {
+                WriterState::Idle => return Poll::Ready(Ok(())),
+                WriterState::Converting(converting) => {
+                    let (converted, stages) = match Pin::new(converting).poll(cx) {
+                        Poll::Ready(result) => result.map_err(join_error)?,
+                        Poll::Pending => return Poll::Pending,
+                    };
+
+                    self.stages = stages;
+                    self.state = WriterState::Writing {
+                        output:  converted.into_bytes(),
+                        written: 0,
+                    };
+                },
+                WriterState::Writing {
+                    output,
+                    written,
+                } => {
+                    while *written < output.len() {
+                        match Pin::new(&mut self.inner).poll_write(cx, &output[*written..]) {
+                            Poll::Ready(Ok(0)) => {
+                                return Poll::Ready(Err(io::ErrorKind::WriteZero.into()))
+                            },
+                            Poll::Ready(Ok(n)) => *written += n,
+                            Poll::Ready(Err(error)) => return Poll::Ready(Err(error)),
+                            Poll::Pending => return Poll::Pending,
+                        }
+                    }
+
+                    self.state = WriterState::Idle;
+                },
+            }
+        }
+    }
+
+    /// Convert and write all the collected text.
+    fn poll_write_pending(
+        &mut self,
+        cx: &mut Context<'_>,
+        allow_incomplete: bool,
+    ) -> Poll<io::Result<()>> {
+        match self.poll_drain(cx) {
+            Poll::Ready(Ok(())) => (),
+            other => return other,
+        }
+
+        let end = valid_prefix(&self.pending, allow_incomplete)?.len();
+
+        if end > 0 || self.stages.has_held() {
+            self.state = WriterState::Converting(spawn_conversion(
+                &self.opencc,
+                &mut self.pending,
+                end,
+                &mut self.stages,
+                true,
+            ));
+
+            return self.poll_drain(cx);
+        }
+
+        Poll::Ready(Ok(()))
+    }
+}
+
+impl<W: AsyncWrite + Unpin> AsyncWrite for ConvertAsyncWriter<W> {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = self.get_mut();
+
+        match this.poll_drain(cx) {
+            Poll::Ready(Ok(())) => (),
+            Poll::Ready(Err(error)) => return Poll::Ready(Err(error)),
+            Poll::Pending => return Poll::Pending,
+        }
+
+        this.pending.extend_from_slice(buf);
+
+        if let Some(end) = piece_end(valid_prefix(&this.pending, true)?) {
+            this.state = WriterState::Converting(spawn_conversion(
+                &this.opencc,
+                &mut this.pending,
+                end,
+                &mut this.stages,
+                false,
+            ));
+        }
+
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        match this.poll_write_pending(cx, true) {
+            Poll::Ready(Ok(())) => Pin::new(&mut this.inner).poll_flush(cx),
+            other => other,
+        }
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+
+        match this.poll_write_pending(cx, false) {
+            Poll::Ready(Ok(())) => Pin::new(&mut this.inner).poll_shutdown(cx),
+            other => other,
+        }
+    }
+}
+
+impl OpenCC {
+    /// Convert a string to another string on the blocking threads of tokio, so the runtime is not blocked.
+    ///
+    /// ```rust,ignore
+    /// let opencc = OpenCC::shared(DefaultConfig::S2TWP).unwrap();
+    ///
+    /// assert_eq!("涼風有訊", &opencc.convert_async("凉风有讯").await);
+    /// ```
+    pub async fn convert_async<S: Into<String>>(self: Arc<Self>, input: S) -> String {
+        let input = input.into();
+
+        match spawn_blocking(move || self.convert(input)).await {
+            Ok(converted) => converted,
+            Err(error) => std::panic::resume_unwind(error.into_panic()),
+        }
+    }
+}
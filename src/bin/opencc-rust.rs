@@ -0,0 +1,325 @@
+use std::{
+    error::Error,
+    fs,
+    io::{self, IsTerminal, Read, Write},
+    path::{Path, PathBuf},
+    process,
+};
+
+use clap::{Parser, ValueEnum};
+use opencc_rust::{
+    convert_directory, convert_docx, convert_epub, convert_odt, find_changes, DefaultConfig,
+    DirectoryOptions, EpubOptions, Format, OpenCC, Punctuation,
+};
+use serde_json::json;
+use similar::TextDiff;
+
+/// The output of `--diff`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum DiffFormat {
+    /// A unified diff, colored if stdout is a terminal
+    Unified,
+    /// A JSON list of the changes, with their byte ranges
+    Json,
+}
+
+/// Convert text between Traditional Chinese and Simplified Chinese with OpenCC.
+#[derive(Debug, Parser)]
+#[command(name = "opencc-rust", version)]
+struct Args {
+    /// A default config name (e.g. s2twp) or the path of an OpenCC config file
+    #[arg(short, long)]
+    config:           String,
+    /// The output file or the output directory (the mirror tree)
+    #[arg(short, long, conflicts_with = "in_place")]
+    output:           Option<PathBuf>,
+    /// Overwrite the input file(s)
+    #[arg(short, long)]
+    in_place:         bool,
+    /// Glob patterns of the files to convert in a directory
+    #[arg(long)]
+    include:          Vec<String>,
+    /// Glob patterns of the files to skip in a directory
+    #[arg(long)]
+    exclude:          Vec<String>,
+    /// Copy the permissions of the source files to the output files
+    #[arg(long)]
+    keep_permissions: bool,
+    /// Keep the modification times of the source files
+    #[arg(long)]
+    keep_mtime:       bool,
+    /// The number of worker threads (0 means the number of logical CPUs)
+    #[arg(short = 'j', long, default_value_t = 0)]
+    threads:          usize,
+    /// Convert the names of files and directories in a directory, too
+    #[arg(long)]
+    convert_names:    bool,
+    /// Only print what would be done
+    #[arg(long)]
+    dry_run:          bool,
+    /// Localize the punctuation (such as quotation marks) for the region of a default config
+    #[arg(long)]
+    punctuation:      bool,
+    /// Convert the input as plain text, instead of choosing the format by the file extension
+    #[arg(long, conflicts_with = "format")]
+    plain:            bool,
+    /// The format of the input (txt, html, md, json, yaml, toml, po, srt, vtt, ass, or a programming language like rs), such as for stdin
+    #[arg(long)]
+    format:           Option<Format>,
+    /// The language tag to set in the metadata of an EPUB file (the target of a default config by default, e.g. zh-TW for s2twp)
+    #[arg(long)]
+    language:         Option<String>,
+    /// Print what the conversion changes instead of the converted text
+    #[arg(
+        long,
+        value_enum,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "unified",
+        conflicts_with_all = ["output", "in_place"]
+    )]
+    diff:             Option<DiffFormat>,
+    /// The input file or directory. Read from stdin if it is not set
+    input:            Option<PathBuf>,
+}
+
+fn open_opencc(config: &str, punctuation: bool) -> Result<OpenCC, &'static str> {
+    match config.parse::<DefaultConfig>() {
+        Ok(config) => {
+            let mut opencc = OpenCC::new(config)?;
+
+            if punctuation {
+                let style = config
+                    .get_punctuation_style()
+                    .ok_or("--punctuation needs a default config which converts to a region.")?;
+
+                opencc.set_punctuation(Some(Punctuation::new(style)));
+            }
+
+            Ok(opencc)
+        },
+        Err(_) if punctuation => {
+            Err("--punctuation needs a default config which converts to a region.")
+        },
+        Err(_) => OpenCC::new(config),
+    }
+}
+
+fn print_diff(name: &str, source: &str, converted: &str, format: DiffFormat) -> io::Result<()> {
+    let mut stdout = io::stdout().lock();
+
+    match format {
+        DiffFormat::Unified => {
+            let diff = TextDiff::from_lines(source, converted);
+            let diff = diff.unified_diff().header(name, name).to_string();
+
+            let color = stdout.is_terminal();
+
+            for line in diff.split_inclusive('\n') {
+                let code = if line.starts_with("---") || line.starts_with("+++") {
+                    "1"
+                } else if line.starts_with('-') {
+                    "31"
+                } else if line.starts_with('+') {
+                    "32"
+                } else if line.starts_with("@@") {
+                    "36"
+                } else {
+                    ""
+                };
+
+                if color && !code.is_empty() {
+                    let content = line.trim_end_matches('\n');
+
+                    write!(stdout, "\x1b[{}m{}\x1b[0m{}", code, content, &line[content.len()..])?;
+                } else {
+                    stdout.write_all(line.as_bytes())?;
+                }
+            }
+        },
+        DiffFormat::Json => {
+            let changes: Vec<_> = find_changes(source, converted)
+                .into_iter()
+                .map(|change| {
+                    json!({
+                        "source_range": [change.source_range.start, change.source_range.end],
+                        "target_range": [change.target_range.start, change.target_range.end],
+                        "from": change.from,
+                        "to": change.to,
+                    })
+                })
+                .collect();
+
+            serde_json::to_writer_pretty(&mut stdout, &changes)?;
+
+            writeln!(stdout)?;
+        },
+    }
+
+    Ok(())
+}
+
+type PackageConverter = fn(&OpenCC, &Path, &Path, &EpubOptions) -> io::Result<()>;
+
+/// The converter of a zip-based document, which cannot be converted as text.
+fn package_converter(path: &Path) -> Option<PackageConverter> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+
+    match extension.as_str() {
+        "epub" => {
+            Some(|opencc, input, output, options| convert_epub(opencc, input, output, options))
+        },
+        "docx" => Some(|opencc, input, output, _| convert_docx(opencc, input, output)),
+        "odt" => Some(|opencc, input, output, _| convert_odt(opencc, input, output)),
+        _ => None,
+    }
+}
+
+fn main() {
+    if let Err(error) = run(Args::parse()) {
+        eprintln!("{}", error);
+
+        process::exit(1);
+    }
+}
+
+fn run(args: Args) -> Result<(), Box<dyn Error>> {
+    let opencc = open_opencc(&args.config, args.punctuation)?;
+
+    let is_dir = args.input.as_ref().map(|input| input.is_dir()).unwrap_or(false);
+
+    if args.convert_names && !is_dir {
+        return Err("--convert-names can only be used with a directory.".into());
+    }
+
+    match args.input {
+        Some(input) if is_dir => {
+            if args.diff.is_some() {
+                return Err("--diff cannot be used with a directory. Use --dry-run instead.".into());
+            }
+
+            if args.output.is_none() && !args.in_place {
+                return Err("Converting a directory needs either --output or --in-place.".into());
+            }
+
+            let options = DirectoryOptions {
+                include:              args.include,
+                exclude:              args.exclude,
+                output:               args.output,
+                preserve_permissions: args.keep_permissions,
+                preserve_mtime:       args.keep_mtime,
+                threads:              args.threads,
+                convert_names:        args.convert_names,
+                dry_run:              args.dry_run,
+                format_aware:         !args.plain,
+            };
+
+            let summary = convert_directory(&opencc, &input, &options)?;
+
+            for path in summary.changed.iter() {
+                println!("changed: {}", path.display());
+            }
+
+            for path in summary.binary.iter() {
+                println!("skipped (binary): {}", path.display());
+            }
+
+            for (source, target) in summary.renamed.iter() {
+                println!("renamed: {} -> {}", source.display(), target.display());
+            }
+
+            for path in summary.non_utf8_names.iter() {
+                println!("kept (non-UTF-8 name): {}", path.display());
+            }
+
+            for collision in summary.collisions.iter() {
+                for source in collision.sources.iter() {
+                    println!("collision: {} -> {}", source.display(), collision.target.display());
+                }
+            }
+
+            println!(
+                "{} changed, {} unchanged, {} skipped, {} renamed",
+                summary.changed.len(),
+                summary.unchanged.len(),
+                summary.binary.len(),
+                summary.renamed.len()
+            );
+        },
+        Some(input) if !args.plain && package_converter(&input).is_some() => {
+            if args.diff.is_some() || args.dry_run {
+                return Err(
+                    "--diff and --dry-run cannot be used with an EPUB, DOCX or ODT file.".into()
+                );
+            }
+
+            let output = match args.output {
+                Some(output) => output,
+                None => return Err("Converting an EPUB, DOCX or ODT file needs --output.".into()),
+            };
+
+            let options = EpubOptions {
+                language: args.language.or_else(|| {
+                    args.config
+                        .parse::<DefaultConfig>()
+                        .ok()
+                        .map(|config| config.get_language_tag().to_string())
+                }),
+            };
+
+            package_converter(&input).unwrap()(&opencc, &input, &output, &options)?;
+        },
+        Some(input) => {
+            let content = fs::read_to_string(&input)?;
+
+            let format = match args.format {
+                Some(format) => format,
+                None if args.plain => Format::Plain,
+                None => Format::from_path(&input),
+            };
+
+            let converted = format.convert(&opencc, &content)?;
+
+            if let Some(diff) = args.diff {
+                print_diff(&input.to_string_lossy(), &content, &converted, diff)?;
+            } else if args.dry_run {
+                if converted != content {
+                    println!("changed: {}", input.display());
+                } else {
+                    println!("unchanged: {}", input.display());
+                }
+            } else if args.in_place {
+                if converted != content {
+                    fs::write(&input, converted)?;
+                }
+            } else {
+                match args.output {
+                    Some(output) => fs::write(output, converted)?,
+                    None => io::stdout().write_all(converted.as_bytes())?,
+                }
+            }
+        },
+        None => {
+            if args.in_place || args.dry_run {
+                return Err("--in-place and --dry-run need an input file or directory.".into());
+            }
+
+            let mut content = String::new();
+            io::stdin().read_to_string(&mut content)?;
+
+            let converted = args.format.unwrap_or(Format::Plain).convert(&opencc, &content)?;
+
+            if let Some(diff) = args.diff {
+                return Ok(print_diff("-", &content, &converted, diff)?);
+            }
+
+            match args.output {
+                Some(output) => fs::write(output, converted)?,
+                None => io::stdout().write_all(converted.as_bytes())?,
+            }
+        },
+    }
+
+    Ok(())
+}
@@ -0,0 +1,136 @@
+use std::{
+    error::Error,
+    fmt::{self, Display, Formatter},
+    ops::Range,
+};
+
+use crate::OpenCC;
+
+/// What `OpenCC::convert_bytes` does with the bytes which are not valid UTF-8.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvalidUtf8Policy {
+    /// Copy the invalid bytes to the output as they are.
+    #[default]
+    PassThrough,
+    /// Fail with the byte ranges of all the invalid sequences, without converting anything.
+    Report,
+}
+
+/// The error of `OpenCC::convert_bytes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConvertBytesError {
+    /// The byte ranges of the invalid UTF-8 sequences of the input, with `InvalidUtf8Policy::Report`.
+    InvalidInput(Vec<Range<usize>>),
+    /// The output of OpenCC is not valid UTF-8, which only happens with a broken dictionary.
+    InvalidOutput,
+}
+
+impl Display for ConvertBytesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            ConvertBytesError::InvalidInput(ranges) => {
+                f.write_str("invalid UTF-8 at bytes ")?;
+
+                for (i, range) in ranges.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str(", ")?;
+                    }
+
+                    write!(f, "{}..{}", range.start, range.end)?;
+                }
+
+                Ok(())
+            },
+            ConvertBytesError::InvalidOutput => f.write_str("OpenCC output invalid UTF-8"),
+        }
+    }
+}
+
+impl Error for ConvertBytesError {}
+
+/// Split bytes into valid UTF-8 runs (`Ok`) and invalid sequences (`Err`), in order.
+fn split_runs(input: &[u8]) -> Vec<Result<&str, Range<usize>>> {
+    let mut runs = Vec::new();
+    let mut offset = 0;
+
+    while offset < input.len() {
+        let rest = &input[offset..];
+
+        match std::str::from_utf8(rest) {
+            Ok(text) => {
+                runs.push(Ok(text));
+
+                break;
+            },
+            Err(error) => {
+                let valid_up_to = error.valid_up_to();
+
+                if valid_up_to > 0 {
+                    runs.push(Ok(std::str::from_utf8(&rest[..valid_up_to]).unwrap()));
+                }
+
+                // an incomplete character at the end is invalid as well
+                let invalid_length = error.error_len().unwrap_or(rest.len() - valid_up_to);
+
+                let start = offset + valid_up_to;
+
+                runs.push(Err(start..(start + invalid_length)));
+
+                offset = start + invalid_length;
+            },
+        }
+    }
+
+    runs
+}
+
+impl OpenCC {
+    /// Convert bytes which are mostly UTF-8 text, such as the data from a legacy system.
+    ///
+    /// Each valid UTF-8 run is converted on its own, so a phrase is not recognized across invalid bytes. NUL characters are kept as they are. With `InvalidUtf8Policy::Report`, the byte ranges of the invalid sequences are returned as `ConvertBytesError::InvalidInput`.
+    ///
+    /// Unlike `convert`, this fails with `ConvertBytesError::InvalidOutput` if the output of OpenCC is not valid UTF-8.
+    ///
+    /// ```rust,ignore
+    /// let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+    ///
+    /// let input = b"\xFF\xFE\xE5\x87\x89\xE9\xA3\x8E";
+    ///
+    /// assert_eq!(b"\xFF\xFE\xE6\xB6\xBC\xE9\xA2\xA8".to_vec(), opencc.convert_bytes(input, InvalidUtf8Policy::PassThrough).unwrap());
+    /// assert_eq!(ConvertBytesError::InvalidInput(vec![0..1, 1..2]), opencc.convert_bytes(input, InvalidUtf8Policy::Report).unwrap_err());
+    /// ```
+    pub fn convert_bytes<B: AsRef<[u8]>>(
+        &self,
+        input: B,
+        policy: InvalidUtf8Policy,
+    ) -> Result<Vec<u8>, ConvertBytesError> {
+        let input = input.as_ref();
+
+        let runs = split_runs(input);
+
+        if policy == InvalidUtf8Policy::Report {
+            let invalid: Vec<Range<usize>> =
+                runs.iter().filter_map(|run| run.as_ref().err().cloned()).collect();
+
+            if !invalid.is_empty() {
+                return Err(ConvertBytesError::InvalidInput(invalid));
+            }
+        }
+
+        let mut output = Vec::with_capacity(input.len());
+
+        for run in runs {
+            match run {
+                Ok(text) => {
+                    let converted =
+                        self.try_convert(text).map_err(|_| ConvertBytesError::InvalidOutput)?;
+
+                    output.extend_from_slice(converted.as_bytes())
+                },
+                Err(range) => output.extend_from_slice(&input[range]),
+            }
+        }
+
+        Ok(output)
+    }
+}
@@ -0,0 +1,132 @@
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex,
+    },
+};
+
+use lru::LruCache;
+
+use crate::OpenCC;
+
+/// Options for `CachedOpenCC`.
+#[derive(Debug, Clone)]
+pub struct CacheOptions {
+    /// The maximum number of cached conversions. Zero disables the cache.
+    pub capacity:       usize,
+    /// The maximum length (in bytes) of an input to be cached. Longer inputs are converted directly, so a few large documents do not evict many short strings.
+    pub max_key_length: usize,
+}
+
+impl Default for CacheOptions {
+    #[inline]
+    fn default() -> Self {
+        CacheOptions {
+            capacity: 4096, max_key_length: 256
+        }
+    }
+}
+
+/// The metrics of a `CachedOpenCC`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheMetrics {
+    pub hits:     u64,
+    pub misses:   u64,
+    /// The number of inputs which were too long to be cached.
+    pub bypasses: u64,
+    /// The number of cached conversions.
+    pub len:      usize,
+}
+
+/// An `OpenCC` instance with a bounded, thread-safe LRU cache of conversions, keyed by the input string.
+///
+/// It is meant for short strings which are converted over and over, like menu labels and product names.
+pub struct CachedOpenCC {
+    opencc:         OpenCC,
+    cache:          Option<Mutex<LruCache<String, String>>>,
+    max_key_length: usize,
+    hits:           AtomicU64,
+    misses:         AtomicU64,
+    bypasses:       AtomicU64,
+}
+
+impl CachedOpenCC {
+    /// Wrap an `OpenCC` instance.
+    pub fn new(opencc: OpenCC, options: CacheOptions) -> CachedOpenCC {
+        CachedOpenCC {
+            opencc,
+            cache: NonZeroUsize::new(options.capacity)
+                .map(|capacity| Mutex::new(LruCache::new(capacity))),
+            max_key_length: options.max_key_length,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            bypasses: AtomicU64::new(0),
+        }
+    }
+
+    /// Convert a string to another string, through the cache.
+    pub fn convert<S: AsRef<str>>(&self, input: S) -> String {
+        let input = input.as_ref();
+
+        let cache = match self.cache.as_ref() {
+            Some(cache) if input.len() <= self.max_key_length => cache,
+            _ => {
+                self.bypasses.fetch_add(1, Ordering::Relaxed);
+
+                return self.opencc.convert(input);
+            },
+        };
+
+        if let Some(converted) = cache.lock().unwrap().get(input) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+
+            return converted.clone();
+        }
+
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        // the lock is not held during the conversion, so other threads are not blocked by it
+        let converted = self.opencc.convert(input);
+
+        cache.lock().unwrap().put(input.to_string(), converted.clone());
+
+        converted
+    }
+
+    /// Get the metrics of the cache.
+    pub fn metrics(&self) -> CacheMetrics {
+        CacheMetrics {
+            hits:     self.hits.load(Ordering::Relaxed),
+            misses:   self.misses.load(Ordering::Relaxed),
+            bypasses: self.bypasses.load(Ordering::Relaxed),
+            len:      self.cache.as_ref().map(|cache| cache.lock().unwrap().len()).unwrap_or(0),
+        }
+    }
+
+    /// Remove all the cached conversions, such as after the dictionaries are changed. The metrics are kept.
+    pub fn clear(&self) {
+        if let Some(cache) = self.cache.as_ref() {
+            cache.lock().unwrap().clear();
+        }
+    }
+
+    /// Replace the wrapped `OpenCC` instance and clear the cache. The old instance is returned.
+    pub fn replace(&mut self, opencc: OpenCC) -> OpenCC {
+        self.clear();
+
+        std::mem::replace(&mut self.opencc, opencc)
+    }
+
+    /// Get the wrapped `OpenCC` instance.
+    #[inline]
+    pub fn get_opencc(&self) -> &OpenCC {
+        &self.opencc
+    }
+
+    /// Unwrap the `OpenCC` instance.
+    #[inline]
+    pub fn into_inner(self) -> OpenCC {
+        self.opencc
+    }
+}
@@ -0,0 +1,192 @@
+use std::ops::Range;
+
+use similar::{capture_diff_slices, Algorithm, DiffOp};
+
+/// A difference between a source string and its conversion, found by `find_changes`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    /// The byte range of the source.
+    pub source_range: Range<usize>,
+    /// The byte range of the converted string.
+    pub target_range: Range<usize>,
+    pub from:         String,
+    pub to:           String,
+}
+
+/// The byte offsets of `lines`, plus the total length.
+fn line_offsets(lines: &[&str]) -> Vec<usize> {
+    let mut offsets = vec![0];
+
+    for line in lines {
+        offsets.push(offsets.last().unwrap() + line.len());
+    }
+
+    offsets
+}
+
+/// The byte offsets of the characters of `text`, plus the length of `text`.
+fn char_offsets(text: &str) -> Vec<usize> {
+    text.char_indices().map(|(i, _)| i).chain(Some(text.len())).collect()
+}
+
+/// Group the consecutive non-equal operations of a diff into ranges of items of the old and the new sequences.
+fn changed_ranges(ops: &[DiffOp]) -> Vec<(Range<usize>, Range<usize>)> {
+    let mut ranges: Vec<(Range<usize>, Range<usize>)> = Vec::new();
+    let mut is_adjacent = false;
+
+    for op in ops {
+        if let DiffOp::Equal {
+            ..
+        } = op
+        {
+            is_adjacent = false;
+
+            continue;
+        }
+
+        let (old, new) = (op.old_range(), op.new_range());
+
+        match ranges.last_mut() {
+            Some(last) if is_adjacent => {
+                last.0.end = old.end;
+                last.1.end = new.end;
+            },
+            _ => ranges.push((old, new)),
+        }
+
+        is_adjacent = true;
+    }
+
+    ranges
+}
+
+/// The most characters between two changes which can still be in one phrase.
+const PHRASE_GAP: usize = 3;
+
+/// Merge every insertion or deletion into the nearest change of the same phrase, so a phrase converted to a phrase with some of the same characters (like `打印机` to `印表機`) is reported as one change.
+///
+/// Two changes are in the same phrase if at most `PHRASE_GAP` characters are between them, none of which is whitespace or punctuation.
+fn merge_phrases(
+    mut ranges: Vec<(Range<usize>, Range<usize>)>,
+    chars: &[char],
+) -> Vec<(Range<usize>, Range<usize>)> {
+    let gap = |ranges: &[(Range<usize>, Range<usize>)], i: usize| {
+        let between = &chars[ranges[i].0.end..ranges[i + 1].0.start];
+
+        if between.len() <= PHRASE_GAP && between.iter().all(|c| c.is_alphanumeric()) {
+            Some(between.len())
+        } else {
+            None
+        }
+    };
+
+    let mut i = 0;
+
+    while i < ranges.len() {
+        if !ranges[i].0.is_empty() && !ranges[i].1.is_empty() {
+            i += 1;
+
+            continue;
+        }
+
+        let before = if i > 0 { gap(&ranges, i - 1) } else { None };
+        let after = if i + 1 < ranges.len() { gap(&ranges, i) } else { None };
+
+        let first = match (before, after) {
+            (Some(before), Some(after)) if after < before => i,
+            (Some(_), _) => i - 1,
+            (None, Some(_)) => i,
+            (None, None) => {
+                i += 1;
+
+                continue;
+            },
+        };
+
+        let (old, new) = ranges.remove(first + 1);
+
+        ranges[first].0.end = old.end;
+        ranges[first].1.end = new.end;
+
+        i = first;
+    }
+
+    ranges
+}
+
+/// Map a byte range of the source of `changes` to the target, widening it to the changes it overlaps.
+pub(crate) fn map_range(changes: &[Change], range: Range<usize>) -> Range<usize> {
+    let map = |offset: usize, is_end: bool| {
+        let mut mapped = offset as isize;
+
+        for change in changes {
+            let source = &change.source_range;
+
+            if source.end < offset || (source.end == offset && !is_end) {
+                mapped += change.target_range.len() as isize - source.len() as isize;
+            } else if source.start < offset && offset < source.end {
+                return if is_end { change.target_range.end } else { change.target_range.start };
+            } else if source.end == offset {
+                return change.target_range.end;
+            } else {
+                break;
+            }
+        }
+
+        mapped as usize
+    };
+
+    let start = map(range.start, false);
+
+    start..map(range.end, true).max(start)
+}
+
+/// Find the differences between a source string and its conversion.
+///
+/// Lines are compared first, and then the characters of the changed lines. A phrase converted to a phrase of another length, such as `打印机` to `印表機`, is reported as one change over the whole phrase, not as the characters which differ.
+pub fn find_changes<S: AsRef<str>, T: AsRef<str>>(source: S, target: T) -> Vec<Change> {
+    let source = source.as_ref();
+    let target = target.as_ref();
+
+    let source_lines: Vec<&str> = source.split_inclusive('\n').collect();
+    let target_lines: Vec<&str> = target.split_inclusive('\n').collect();
+
+    let source_line_offsets = line_offsets(&source_lines);
+    let target_line_offsets = line_offsets(&target_lines);
+
+    let line_ops = capture_diff_slices(Algorithm::Myers, &source_lines, &target_lines);
+
+    let mut changes = Vec::new();
+
+    for (old, new) in changed_ranges(&line_ops) {
+        let source_start = source_line_offsets[old.start];
+        let target_start = target_line_offsets[new.start];
+
+        let source_block = &source[source_start..source_line_offsets[old.end]];
+        let target_block = &target[target_start..target_line_offsets[new.end]];
+
+        let source_chars: Vec<char> = source_block.chars().collect();
+        let target_chars: Vec<char> = target_block.chars().collect();
+
+        let source_char_offsets = char_offsets(source_block);
+        let target_char_offsets = char_offsets(target_block);
+
+        let char_ops = capture_diff_slices(Algorithm::Myers, &source_chars, &target_chars);
+
+        for (old, new) in merge_phrases(changed_ranges(&char_ops), &source_chars) {
+            let source_range = (source_start + source_char_offsets[old.start])
+                ..(source_start + source_char_offsets[old.end]);
+            let target_range = (target_start + target_char_offsets[new.start])
+                ..(target_start + target_char_offsets[new.end]);
+
+            changes.push(Change {
+                from: source[source_range.clone()].to_string(),
+                to: target[target_range.clone()].to_string(),
+                source_range,
+                target_range,
+            });
+        }
+    }
+
+    changes
+}
@@ -0,0 +1,226 @@
+use std::{
+    io::{self, BufRead, Write},
+    ops::Range,
+};
+
+use crate::OpenCC;
+
+/// A column of a CSV file, selected by its header name or by its (zero-based) index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CsvColumn {
+    Name(String),
+    Index(usize),
+}
+
+/// Options for `convert_csv`.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// The field delimiter, such as `b','` for CSV or `b'\t'` for TSV.
+    pub delimiter:  u8,
+    /// Whether the first record is a header. The header is never converted.
+    pub has_header: bool,
+    /// The columns to convert. If it is empty, every column is converted.
+    pub columns:    Vec<CsvColumn>,
+}
+
+impl Default for CsvOptions {
+    #[inline]
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: b',', has_header: true, columns: Vec::new()
+        }
+    }
+}
+
+/// Where a byte of a record is. As in RFC 4180, a quote opens a quoted field only at the start of the field, and is kept as text elsewhere.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FieldState {
+    Start,
+    Unquoted,
+    Quoted,
+    /// A quote in a quoted field, which either closes the field or escapes another quote.
+    QuoteInQuoted,
+}
+
+impl FieldState {
+    #[inline]
+    fn next(self, b: u8, delimiter: u8) -> FieldState {
+        match self {
+            FieldState::Quoted => {
+                if b == b'"' {
+                    FieldState::QuoteInQuoted
+                } else {
+                    FieldState::Quoted
+                }
+            },
+            _ if b == delimiter => FieldState::Start,
+            FieldState::Start if b == b'"' => FieldState::Quoted,
+            FieldState::QuoteInQuoted if b == b'"' => FieldState::Quoted,
+            _ => FieldState::Unquoted,
+        }
+    }
+}
+
+/// Read a record, which can span several lines if a quoted field contains line breaks. Returns `false` at the end of the input.
+fn read_record<R: BufRead>(
+    reader: &mut R,
+    record: &mut Vec<u8>,
+    delimiter: u8,
+) -> io::Result<bool> {
+    record.clear();
+
+    let mut state = FieldState::Start;
+
+    loop {
+        let start = record.len();
+
+        if reader.read_until(b'\n', record)? == 0 {
+            return Ok(!record.is_empty());
+        }
+
+        for &b in record[start..].iter() {
+            state = state.next(b, delimiter);
+        }
+
+        if state != FieldState::Quoted {
+            return Ok(true);
+        }
+    }
+}
+
+/// Split a record into the ranges of its fields (including their quotes), without the line ending.
+fn split_fields(record: &[u8], delimiter: u8) -> Vec<Range<usize>> {
+    let mut end = record.len();
+
+    if record[..end].ends_with(b"\n") {
+        end -= 1;
+    }
+
+    if record[..end].ends_with(b"\r") {
+        end -= 1;
+    }
+
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut state = FieldState::Start;
+
+    for (i, &b) in record[..end].iter().enumerate() {
+        state = state.next(b, delimiter);
+
+        if state == FieldState::Start {
+            fields.push(start..i);
+
+            start = i + 1;
+        }
+    }
+
+    fields.push(start..end);
+
+    fields
+}
+
+/// The value of a header field, without the BOM and the quotes.
+fn header_name(field: &[u8]) -> String {
+    let field = String::from_utf8_lossy(field);
+    let field = field.trim_start_matches('\u{feff}');
+
+    if field.len() >= 2 && field.starts_with('"') && field.ends_with('"') {
+        field[1..(field.len() - 1)].replace("\"\"", "\"")
+    } else {
+        field.to_string()
+    }
+}
+
+/// Resolve the selected columns to indices. `None` means every column.
+fn resolve_columns(
+    options: &CsvOptions,
+    header: Option<(&[u8], &[Range<usize>])>,
+) -> io::Result<Option<Vec<usize>>> {
+    if options.columns.is_empty() {
+        return Ok(None);
+    }
+
+    options
+        .columns
+        .iter()
+        .map(|column| match column {
+            CsvColumn::Index(index) => Ok(*index),
+            CsvColumn::Name(name) => {
+                let (record, fields) = header.ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        "A column is selected by its name, but the CSV has no header.",
+                    )
+                })?;
+
+                fields
+                    .iter()
+                    .position(|field| header_name(&record[field.clone()]) == *name)
+                    .ok_or_else(|| {
+                        io::Error::new(
+                            io::ErrorKind::InvalidInput,
+                            format!("The column {:?} is not in the header.", name),
+                        )
+                    })
+            },
+        })
+        .collect::<io::Result<_>>()
+        .map(Some)
+}
+
+/// Convert the selected columns of a CSV (or TSV) stream record by record, keeping the quoting, the delimiters and the line endings.
+///
+/// Quoted fields may contain delimiters, escaped quotes (`""`) and line breaks. A quote which is not at the start of a field is kept as text, like in `5" disk`. Fields which are not valid UTF-8 are kept. The header, if any, is written as it is.
+pub fn convert_csv<R: BufRead, W: Write>(
+    opencc: &OpenCC,
+    mut reader: R,
+    mut writer: W,
+    options: &CsvOptions,
+) -> io::Result<()> {
+    let mut record = Vec::new();
+    let mut selected = None;
+    let mut is_first = true;
+
+    while read_record(&mut reader, &mut record, options.delimiter)? {
+        let fields = split_fields(&record, options.delimiter);
+
+        if is_first {
+            is_first = false;
+
+            let header = if options.has_header {
+                Some((record.as_slice(), fields.as_slice()))
+            } else {
+                None
+            };
+
+            selected = resolve_columns(options, header)?;
+
+            if options.has_header {
+                writer.write_all(&record)?;
+
+                continue;
+            }
+        }
+
+        let mut offset = 0;
+
+        for (index, field) in fields.into_iter().enumerate() {
+            if selected.as_ref().map(|selected| selected.contains(&index)).unwrap_or(true) {
+                if let Ok(text) = std::str::from_utf8(&record[field.clone()]) {
+                    let converted = opencc.convert_in_document(text);
+
+                    if converted != text {
+                        writer.write_all(&record[offset..field.start])?;
+                        writer.write_all(converted.as_bytes())?;
+
+                        offset = field.end;
+                    }
+                }
+            }
+        }
+
+        writer.write_all(&record[offset..])?;
+    }
+
+    writer.flush()
+}
@@ -0,0 +1,462 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+use rayon::prelude::*;
+use walkdir::WalkDir;
+
+use crate::{Format, OpenCC};
+
+/// Options for `convert_directory`.
+#[derive(Debug, Clone, Default)]
+pub struct DirectoryOptions {
+    /// Glob patterns (relative to the input directory) of the files to convert. If it is empty, every file is matched.
+    pub include:              Vec<String>,
+    /// Glob patterns (relative to the input directory) of the files and directories to skip, such as `vendor` or `**/*.min.js`.
+    pub exclude:              Vec<String>,
+    /// The directory of the mirror output tree. If it is `None`, files are converted in place.
+    pub output:               Option<PathBuf>,
+    /// Whether to copy the permissions of the source files to the output files.
+    pub preserve_permissions: bool,
+    /// Whether to keep the modification times of the source files on the output files.
+    pub preserve_mtime:       bool,
+    /// The number of worker threads. `0` means the number of logical CPUs.
+    pub threads:              usize,
+    /// Whether to convert the names of the matched files and of the directories which contain them, too.
+    pub convert_names:        bool,
+    /// Only report what would be done, without touching the file system.
+    pub dry_run:              bool,
+    /// Whether to convert the files by the formats of their extensions (see `Format::from_path`), so that only the text of HTML, Markdown, JSON, YAML and TOML files is converted. The `directory` feature enables the `markdown` and `json` features, so no Markdown or JSON file is converted as plain text.
+    pub format_aware:         bool,
+}
+
+/// Source paths which would be converted to the same target path.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NameCollision {
+    /// The converted path, relative to the output directory (or to the input directory when converting in place).
+    pub target:  PathBuf,
+    /// The paths, relative to the input directory, which are converted to `target`. When converting in place, this may contain only one path if `target` already exists.
+    pub sources: Vec<PathBuf>,
+}
+
+/// What happened (or would happen, for a dry run) to the files visited by `convert_directory`. All paths are relative to the input directory and sorted.
+#[derive(Debug, Clone, Default)]
+pub struct DirectorySummary {
+    /// Files whose contents were changed by the conversion.
+    pub changed:        Vec<PathBuf>,
+    /// Files which were matched and converted, but whose contents stayed the same.
+    pub unchanged:      Vec<PathBuf>,
+    /// Files which were matched but not converted because they are not UTF-8 text. They are copied verbatim to the mirror tree.
+    pub binary:         Vec<PathBuf>,
+    /// Files and directories which are renamed, as `(source, target)` pairs.
+    pub renamed:        Vec<(PathBuf, PathBuf)>,
+    /// Name collisions. Only a dry run reports them; otherwise `convert_directory` fails.
+    pub collisions:     Vec<NameCollision>,
+    /// Files and directories whose names are not valid UTF-8 and are therefore kept.
+    pub non_utf8_names: Vec<PathBuf>,
+}
+
+enum FileOutcome {
+    Changed,
+    Unchanged,
+    Binary,
+}
+
+struct Entry {
+    source: PathBuf,
+    target: PathBuf,
+    is_dir: bool,
+}
+
+#[inline]
+fn invalid_input<E: Into<Box<dyn std::error::Error + Send + Sync>>>(error: E) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, error)
+}
+
+fn build_glob_set(patterns: &[String]) -> io::Result<GlobSet> {
+    let mut builder = GlobSetBuilder::new();
+
+    for pattern in patterns {
+        builder.add(Glob::new(pattern).map_err(invalid_input)?);
+    }
+
+    builder.build().map_err(invalid_input)
+}
+
+/// Decode the content of a file as text. The data is given back if the file looks binary.
+fn decode_text(data: Vec<u8>) -> Result<String, Vec<u8>> {
+    if data.contains(&0) {
+        return Err(data);
+    }
+
+    String::from_utf8(data).map_err(|error| error.into_bytes())
+}
+
+/// Convert every component of a relative path. Components which are not valid UTF-8 are kept.
+fn convert_relative_path(opencc: &OpenCC, path: &Path) -> PathBuf {
+    let mut target = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => match name.to_str() {
+                Some(name) => target.push(opencc.convert(name)),
+                None => target.push(name),
+            },
+            _ => target.push(component),
+        }
+    }
+
+    target
+}
+
+fn write_file(
+    source: &Path,
+    target: &Path,
+    data: &[u8],
+    options: &DirectoryOptions,
+    in_place: bool,
+) -> io::Result<()> {
+    let metadata = fs::metadata(source)?;
+
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    fs::write(target, data)?;
+
+    if options.preserve_permissions && !in_place {
+        fs::set_permissions(target, metadata.permissions())?;
+    }
+
+    if options.preserve_mtime {
+        filetime::set_file_mtime(
+            target,
+            filetime::FileTime::from_last_modification_time(&metadata),
+        )?;
+    }
+
+    Ok(())
+}
+
+fn convert_file(
+    opencc: &OpenCC,
+    source: &Path,
+    target: &Path,
+    options: &DirectoryOptions,
+) -> io::Result<FileOutcome> {
+    let in_place = source == target;
+
+    let content = match decode_text(fs::read(source)?) {
+        Ok(content) => content,
+        Err(data) => {
+            if !in_place && !options.dry_run {
+                write_file(source, target, &data, options, in_place)?;
+            }
+
+            return Ok(FileOutcome::Binary);
+        },
+    };
+
+    let converted = if options.format_aware {
+        Format::from_path(source).convert(opencc, &content).map_err(|err| {
+            io::Error::new(io::ErrorKind::InvalidData, format!("{}: {}", source.display(), err))
+        })?
+    } else {
+        opencc.convert(&content)
+    };
+    let changed = converted != content;
+
+    if (!in_place || changed) && !options.dry_run {
+        write_file(source, target, converted.as_bytes(), options, in_place)?;
+    }
+
+    if changed {
+        Ok(FileOutcome::Changed)
+    } else {
+        Ok(FileOutcome::Unchanged)
+    }
+}
+
+/// Find an unused path next to `path` to rename it to temporarily.
+fn temporary_path(path: &Path) -> PathBuf {
+    let mut n = 0u32;
+
+    loop {
+        let temporary =
+            path.with_file_name(format!(".opencc-rust-rename-{}-{}", std::process::id(), n));
+
+        if fs::symlink_metadata(&temporary).is_err() {
+            return temporary;
+        }
+
+        n += 1;
+    }
+}
+
+/// Rename entries of the same depth through temporary names.
+///
+/// If a rename fails, the entries which are still at their temporary names are moved back, and the error lists those which cannot be.
+fn rename_level(input: &Path, level: &[&Entry]) -> io::Result<()> {
+    let mut temporaries: Vec<(PathBuf, PathBuf)> = Vec::with_capacity(level.len());
+    let mut result = Ok(());
+
+    for entry in level {
+        let from = input.join(&entry.source);
+        let temporary = temporary_path(&from);
+
+        if let Err(error) = fs::rename(&from, &temporary) {
+            result = Err(error);
+
+            break;
+        }
+
+        temporaries.push((from, temporary));
+    }
+
+    let mut renamed = 0;
+
+    if result.is_ok() {
+        for ((_, temporary), entry) in temporaries.iter().zip(level) {
+            let to = temporary.with_file_name(entry.target.file_name().unwrap());
+
+            if let Err(error) = fs::rename(temporary, to) {
+                result = Err(error);
+
+                break;
+            }
+
+            renamed += 1;
+        }
+    }
+
+    if let Err(error) = result {
+        // an original name may already be taken by another entry, which must not be overwritten
+        let leftovers: Vec<String> = temporaries[renamed..]
+            .iter()
+            .filter(|(from, temporary)| {
+                fs::symlink_metadata(from).is_ok() || fs::rename(temporary, from).is_err()
+            })
+            .map(|(_, temporary)| format!("`{}`", temporary.display()))
+            .collect();
+
+        if leftovers.is_empty() {
+            return Err(error);
+        }
+
+        return Err(io::Error::new(
+            error.kind(),
+            format!(
+                "{} These paths are left with temporary names: {}.",
+                error,
+                leftovers.join(", ")
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+fn find_collisions(entries: &[Entry], input: &Path, in_place: bool) -> Vec<NameCollision> {
+    let mut targets: HashMap<&Path, Vec<PathBuf>> = HashMap::new();
+
+    for entry in entries {
+        targets.entry(entry.target.as_path()).or_default().push(entry.source.clone());
+    }
+
+    let visited: HashSet<&Path> = entries.iter().map(|entry| entry.source.as_path()).collect();
+
+    let mut collisions: Vec<NameCollision> = targets
+        .into_iter()
+        .filter(|(target, sources)| {
+            if sources.len() > 1 {
+                return true;
+            }
+
+            // when renaming in place, an existing path which is not visited would be overwritten
+            in_place
+                && sources[0].as_path() != *target
+                && !visited.contains(target)
+                && fs::symlink_metadata(input.join(target)).is_ok()
+        })
+        .map(|(target, sources)| NameCollision {
+            target: target.to_path_buf(),
+            sources,
+        })
+        .collect();
+
+    collisions.sort_by(|a, b| a.target.cmp(&b.target));
+
+    collisions
+}
+
+/// Walk a directory tree and convert the text files which match the include/exclude globs.
+///
+/// Files which are not valid UTF-8 or contain NUL bytes are treated as binary files. They are not converted, but they are copied verbatim to the mirror tree when `options.output` is set. The files are processed by a pool of worker threads which share the same `OpenCC` instance.
+///
+/// If `options.convert_names` is set, the names of the matched files and of the directories which contain them are converted as well. Names which are not valid UTF-8 are kept. If two paths would end up with the same name, nothing is written and an `AlreadyExists` error is returned, unless it is a dry run, which reports the collisions in the summary instead.
+pub fn convert_directory<P: AsRef<Path>>(
+    opencc: &OpenCC,
+    input: P,
+    options: &DirectoryOptions,
+) -> io::Result<DirectorySummary> {
+    let input = input.as_ref();
+
+    if !input.is_dir() {
+        return Err(invalid_input("The input path needs to be a directory."));
+    }
+
+    let include = build_glob_set(&options.include)?;
+    let exclude = build_glob_set(&options.exclude)?;
+
+    // the mirror tree may be located inside the input directory, so it must not be walked
+    let output = match options.output.as_ref() {
+        Some(output) => match output.canonicalize() {
+            Ok(output) => Some(output),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => None,
+            Err(error) => return Err(error),
+        },
+        None => None,
+    };
+
+    let mut summary = DirectorySummary::default();
+    let mut entries = Vec::new();
+
+    let mut walker = WalkDir::new(input).min_depth(1).sort_by_file_name().into_iter();
+
+    while let Some(entry) = walker.next() {
+        let entry = entry?;
+        let path = entry.path();
+        let relative_path = path.strip_prefix(input).unwrap().to_path_buf();
+        let is_dir = entry.file_type().is_dir();
+
+        if is_dir {
+            let is_output = match output.as_ref() {
+                Some(output) => path.canonicalize()? == *output,
+                None => false,
+            };
+
+            if is_output || exclude.is_match(&relative_path) {
+                walker.skip_current_dir();
+
+                continue;
+            }
+        } else {
+            if !entry.file_type().is_file() || exclude.is_match(&relative_path) {
+                continue;
+            }
+
+            if !options.include.is_empty() && !include.is_match(&relative_path) {
+                continue;
+            }
+        }
+
+        entries.push(Entry {
+            target: relative_path.clone(),
+            source: relative_path,
+            is_dir,
+        });
+    }
+
+    let in_place = options.output.is_none();
+
+    if options.convert_names {
+        // only the directories which contain matched files are renamed
+        let mut parents = HashSet::new();
+
+        for entry in entries.iter().filter(|entry| !entry.is_dir) {
+            parents.extend(entry.source.ancestors().skip(1).map(Path::to_path_buf));
+        }
+
+        entries.retain(|entry| !entry.is_dir || parents.contains(&entry.source));
+
+        for entry in entries.iter_mut() {
+            if entry.source.file_name().and_then(|name| name.to_str()).is_none() {
+                summary.non_utf8_names.push(entry.source.clone());
+            }
+
+            entry.target = convert_relative_path(opencc, &entry.source);
+        }
+
+        summary.collisions = find_collisions(&entries, input, in_place);
+
+        if let Some(collision) = summary.collisions.first() {
+            if !options.dry_run {
+                let sources = collision
+                    .sources
+                    .iter()
+                    .map(|source| format!("`{}`", source.display()))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+
+                return Err(io::Error::new(
+                    io::ErrorKind::AlreadyExists,
+                    format!(
+                        "The name of {} would be converted to `{}`, which is already taken.",
+                        sources,
+                        collision.target.display()
+                    ),
+                ));
+            }
+        }
+
+        for entry in entries.iter() {
+            if entry.source != entry.target {
+                summary.renamed.push((entry.source.clone(), entry.target.clone()));
+            }
+        }
+    }
+
+    let files: Vec<&Entry> = entries.iter().filter(|entry| !entry.is_dir).collect();
+
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(options.threads)
+        .build()
+        .map_err(io::Error::other)?;
+
+    let outcomes = pool.install(|| {
+        files
+            .par_iter()
+            .map(|entry| {
+                let source = input.join(&entry.source);
+
+                // renaming in place happens after all contents are converted
+                let target = match options.output.as_ref() {
+                    Some(output) => output.join(&entry.target),
+                    None => source.clone(),
+                };
+
+                convert_file(opencc, &source, &target, options)
+            })
+            .collect::<io::Result<Vec<FileOutcome>>>()
+    })?;
+
+    for (entry, outcome) in files.into_iter().zip(outcomes) {
+        let source = entry.source.clone();
+
+        match outcome {
+            FileOutcome::Changed => summary.changed.push(source),
+            FileOutcome::Unchanged => summary.unchanged.push(source),
+            FileOutcome::Binary => summary.binary.push(source),
+        }
+    }
+
+    if in_place && options.convert_names && !options.dry_run {
+        // rename the deepest entries first, so that their parents still have the original names
+        let mut renames: Vec<&Entry> =
+            entries.iter().filter(|entry| entry.source != entry.target).collect();
+
+        renames.sort_by_key(|entry| std::cmp::Reverse(entry.source.components().count()));
+
+        // rename through temporary names, so that with a chain like `A` to `B` and `B` to `C`, `B` is moved away before `A` takes its name
+        for level in
+            renames.chunk_by(|a, b| a.source.components().count() == b.source.components().count())
+        {
+            rename_level(input, level)?;
+        }
+    }
+
+    Ok(summary)
+}
@@ -0,0 +1,96 @@
+use std::{io, ops::Range, path::Path};
+
+use crate::{archive::convert_archive, convert_html, segments::apply_replacements, OpenCC};
+
+/// Options for `convert_epub`.
+#[derive(Debug, Clone, Default)]
+pub struct EpubOptions {
+    /// The new `dc:language` of the book, such as `zh-TW` (see `DefaultConfig::get_language_tag`). If it is `None`, the language is kept.
+    pub language: Option<String>,
+}
+
+/// Find the contents of the elements named `name` (such as `dc:title`).
+fn element_contents(xml: &str, name: &str) -> Vec<Range<usize>> {
+    let open = format!("<{}", name);
+    let close = format!("</{}>", name);
+
+    let mut contents = Vec::new();
+    let mut p = 0;
+
+    while let Some(i) = xml[p..].find(&open) {
+        let name_end = p + i + open.len();
+
+        p = name_end;
+
+        if !matches!(xml[name_end..].chars().next(), Some(c) if c == '>' || c == '/' || c.is_whitespace())
+        {
+            continue;
+        }
+
+        let tag_end = match xml[name_end..].find('>') {
+            Some(i) => name_end + i,
+            None => break,
+        };
+
+        p = tag_end + 1;
+
+        if xml[..tag_end].ends_with('/') {
+            continue;
+        }
+
+        let content_end = match xml[p..].find(&close) {
+            Some(i) => p + i,
+            None => break,
+        };
+
+        contents.push(p..content_end);
+
+        p = content_end + close.len();
+    }
+
+    contents
+}
+
+/// Convert the title and the description of an OPF package document, and update its language.
+fn convert_opf(opencc: &OpenCC, opf: &str, options: &EpubOptions) -> String {
+    let mut replacements = Vec::new();
+
+    for name in ["dc:title", "dc:description"] {
+        for range in element_contents(opf, name) {
+            let text = &opf[range.clone()];
+            let converted = opencc.convert_in_document(text);
+
+            if converted != text {
+                replacements.push((range, converted));
+            }
+        }
+    }
+
+    if let Some(language) = options.language.as_ref() {
+        for range in element_contents(opf, "dc:language") {
+            replacements.push((range, language.clone()));
+        }
+    }
+
+    apply_replacements(opf, replacements)
+}
+
+/// Convert an EPUB e-book and write the result to `output`, which may be `input` itself (the result replaces it when it is complete).
+///
+/// The XHTML content documents (including the navigation document) and the NCX are converted with `convert_html`, and so are the title and the description in the OPF package document, whose `dc:language` is set to `options.language`. Images, stylesheets, fonts and the other entries are copied as they are, and the `mimetype` entry is written first without compression.
+pub fn convert_epub<P: AsRef<Path>, Q: AsRef<Path>>(
+    opencc: &OpenCC,
+    input: P,
+    output: Q,
+    options: &EpubOptions,
+) -> io::Result<()> {
+    convert_archive(input.as_ref(), output.as_ref(), |name, content| {
+        let extension = Path::new(name).extension()?.to_str()?.to_ascii_lowercase();
+
+        match extension.as_str() {
+            "xhtml" | "html" | "htm" | "ncx" => Some(convert_html(opencc, content)),
+            "opf" => Some(convert_opf(opencc, content, options)),
+            _ => None,
+        }
+    })
+}
@@ -0,0 +1,99 @@
+use std::{path::Path, str::FromStr};
+
+#[cfg(feature = "markdown")]
+use crate::convert_markdown;
+use crate::{
+    convert_ass, convert_html, convert_po, convert_source, convert_srt, convert_toml, convert_vtt,
+    convert_yaml, Language, OpenCC, PoOptions, ResourceOptions, SourceOptions,
+};
+#[cfg(feature = "json")]
+use crate::{convert_json, JsonOptions};
+
+/// A document format which can be converted without touching its syntax.
+///
+/// Some variants exist only with their features (`Markdown` and `Json`), so a `match` on it needs a wildcard arm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Format {
+    /// Plain text. The whole content is converted.
+    Plain,
+    /// HTML or XHTML, converted by `convert_html`.
+    Html,
+    /// Markdown, converted by `convert_markdown`.
+    #[cfg(feature = "markdown")]
+    Markdown,
+    /// JSON, converted by `convert_json`.
+    #[cfg(feature = "json")]
+    Json,
+    /// YAML, converted by `convert_yaml`.
+    Yaml,
+    /// TOML, converted by `convert_toml`.
+    Toml,
+    /// Gettext PO or POT, converted by `convert_po`.
+    Po,
+    /// SRT subtitles, converted by `convert_srt`.
+    Srt,
+    /// WebVTT subtitles, converted by `convert_vtt`.
+    Vtt,
+    /// ASS or SSA subtitles, converted by `convert_ass`.
+    Ass,
+    /// Source code. Only comments and string literals are converted.
+    Source(Language),
+}
+
+impl Format {
+    /// Guess the format of a file by its extension. Unknown extensions are treated as plain text, and so are `.md` and `.json` files without the `markdown` and `json` features.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Format {
+        path.as_ref()
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .and_then(|extension| extension.parse().ok())
+            .unwrap_or(Format::Plain)
+    }
+
+    /// Convert a document of this format with the default options of the format.
+    pub fn convert<S: AsRef<str>>(self, opencc: &OpenCC, text: S) -> Result<String, &'static str> {
+        let text = text.as_ref();
+
+        Ok(match self {
+            Format::Plain => opencc.convert(text),
+            Format::Html => convert_html(opencc, text),
+            #[cfg(feature = "markdown")]
+            Format::Markdown => convert_markdown(opencc, text),
+            #[cfg(feature = "json")]
+            Format::Json => convert_json(opencc, text, &JsonOptions::default())?,
+            Format::Yaml => convert_yaml(opencc, text, &ResourceOptions::default()),
+            Format::Toml => convert_toml(opencc, text, &ResourceOptions::default()),
+            Format::Po => convert_po(opencc, text, &PoOptions::default()),
+            Format::Srt => convert_srt(opencc, text),
+            Format::Vtt => convert_vtt(opencc, text),
+            Format::Ass => convert_ass(opencc, text),
+            Format::Source(language) => {
+                convert_source(opencc, text, language, &SourceOptions::default())
+            },
+        })
+    }
+}
+
+impl FromStr for Format {
+    type Err = &'static str;
+
+    /// Parse a format name or a file extension, such as `html`, `md` or `yml`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "txt" | "text" | "plain" => Format::Plain,
+            "html" | "htm" | "xhtml" => Format::Html,
+            #[cfg(feature = "markdown")]
+            "md" | "markdown" => Format::Markdown,
+            #[cfg(feature = "json")]
+            "json" => Format::Json,
+            "yaml" | "yml" => Format::Yaml,
+            "toml" => Format::Toml,
+            "po" | "pot" => Format::Po,
+            "srt" => Format::Srt,
+            "vtt" => Format::Vtt,
+            "ass" | "ssa" => Format::Ass,
+            _ => return s.parse().map(Format::Source).map_err(|_| "Unknown document format."),
+        })
+    }
+}
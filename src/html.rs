@@ -0,0 +1,451 @@
+use crate::{segments::convert_segments, OpenCC};
+
+/// Elements whose contents are never converted.
+const SKIPPED_ELEMENTS: [&str; 4] = ["script", "style", "code", "pre"];
+
+/// Elements whose contents are raw text, which may contain `<` without starting a tag.
+const RAW_TEXT_ELEMENTS: [&str; 2] = ["script", "style"];
+
+/// Elements which have no contents and no end tag.
+const VOID_ELEMENTS: [&str; 14] = [
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Elements which do not break the text flow, so that phrases can span over them.
+const INLINE_ELEMENTS: [&str; 22] = [
+    "a", "abbr", "b", "bdi", "bdo", "cite", "data", "dfn", "em", "font", "i", "mark", "q", "rb",
+    "s", "small", "span", "strong", "sub", "sup", "time", "u",
+];
+
+/// Attributes whose values are converted.
+const CONVERTED_ATTRIBUTES: [&str; 3] = ["title", "alt", "placeholder"];
+
+enum Piece<'a> {
+    Text(&'a str),
+    Markup(String),
+}
+
+struct Element {
+    name:         String,
+    in_code:      bool,
+    translate_no: bool,
+}
+
+impl Element {
+    #[inline]
+    fn is_skipped(&self) -> bool {
+        self.in_code || self.translate_no
+    }
+}
+
+struct Attribute<'a> {
+    name:        String,
+    value:       Option<&'a str>,
+    /// The byte range of the value (without quotes) in the tag.
+    value_range: (usize, usize),
+}
+
+struct Tag<'a> {
+    name:         String,
+    attributes:   Vec<Attribute<'a>>,
+    self_closing: bool,
+    /// The byte length of the whole tag.
+    length:       usize,
+}
+
+impl<'a> Tag<'a> {
+    fn attribute(&self, name: &str) -> Option<&'a str> {
+        self.attributes.iter().find(|attribute| attribute.name == name).and_then(|a| a.value)
+    }
+}
+
+struct Converter<'a, 'b> {
+    opencc:  &'b OpenCC,
+    output:  String,
+    pending: Vec<Piece<'a>>,
+    stack:   Vec<Element>,
+}
+
+impl<'a, 'b> Converter<'a, 'b> {
+    #[inline]
+    fn is_skipped(&self) -> bool {
+        self.stack.last().map(|element| element.is_skipped()).unwrap_or(false)
+    }
+
+    fn push_text(&mut self, text: &'a str) {
+        if text.is_empty() {
+            return;
+        }
+
+        if self.is_skipped() {
+            self.pending.push(Piece::Markup(text.to_string()));
+        } else {
+            self.pending.push(Piece::Text(text));
+        }
+    }
+
+    #[inline]
+    fn push_markup<S: Into<String>>(&mut self, markup: S) {
+        self.pending.push(Piece::Markup(markup.into()));
+    }
+
+    /// Convert the pending text as one unit and write everything pending to the output.
+    fn flush(&mut self) {
+        let texts: Vec<&str> = self
+            .pending
+            .iter()
+            .filter_map(|piece| match piece {
+                Piece::Text(text) => Some(*text),
+                Piece::Markup(_) => None,
+            })
+            .collect();
+
+        let mut converted = convert_segments(self.opencc, &texts).into_iter();
+
+        for piece in self.pending.drain(..) {
+            match piece {
+                Piece::Text(_) => self.output.push_str(&converted.next().unwrap()),
+                Piece::Markup(markup) => self.output.push_str(&markup),
+            }
+        }
+    }
+
+    fn start_tag(&mut self, source: &'a str, tag: Tag<'a>) {
+        let parent_in_code = self.stack.last().map(|element| element.in_code).unwrap_or(false);
+        let parent_translate_no =
+            self.stack.last().map(|element| element.translate_no).unwrap_or(false);
+
+        let element = Element {
+            in_code:      parent_in_code || SKIPPED_ELEMENTS.contains(&tag.name.as_str()),
+            translate_no: match tag.attribute("translate").map(|v| v.to_ascii_lowercase()) {
+                Some(v) if v == "no" => true,
+                Some(v) if v.is_empty() || v == "yes" => false,
+                _ => parent_translate_no,
+            },
+            name:         tag.name.clone(),
+        };
+
+        let markup = if element.is_skipped() {
+            source[..tag.length].to_string()
+        } else {
+            self.convert_attributes(source, &tag)
+        };
+
+        let is_void = tag.self_closing || VOID_ELEMENTS.contains(&tag.name.as_str());
+
+        let keeps_flow = INLINE_ELEMENTS.contains(&tag.name.as_str())
+            && element.is_skipped() == self.is_skipped();
+
+        if !keeps_flow {
+            self.flush();
+        }
+
+        self.push_markup(markup);
+
+        if !is_void {
+            self.stack.push(element);
+        }
+    }
+
+    fn end_tag(&mut self, markup: &'a str, name: &str) {
+        let keeps_flow = INLINE_ELEMENTS.contains(&name)
+            && match self.stack.iter().rposition(|element| element.name == name) {
+                Some(index) => {
+                    let outer_skipped = index > 0 && self.stack[index - 1].is_skipped();
+
+                    self.stack[index].is_skipped() == outer_skipped
+                },
+                None => true,
+            };
+
+        if !keeps_flow {
+            self.flush();
+        }
+
+        self.push_markup(markup);
+
+        if let Some(index) = self.stack.iter().rposition(|element| element.name == name) {
+            self.stack.truncate(index);
+        }
+    }
+
+    fn convert_attributes(&self, source: &'a str, tag: &Tag<'a>) -> String {
+        let is_meta_description = tag.name == "meta"
+            && tag
+                .attribute("name")
+                .map(|name| name.eq_ignore_ascii_case("description"))
+                .unwrap_or(false);
+
+        let mut markup = String::with_capacity(tag.length);
+        let mut offset = 0;
+
+        for attribute in tag.attributes.iter() {
+            let value = match attribute.value {
+                Some(value) => value,
+                None => continue,
+            };
+
+            let is_converted = CONVERTED_ATTRIBUTES.contains(&attribute.name.as_str())
+                || (is_meta_description && attribute.name == "content");
+
+            if !is_converted {
+                continue;
+            }
+
+            let (start, end) = attribute.value_range;
+
+            markup.push_str(&source[offset..start]);
+            markup.push_str(&self.opencc.convert_in_document(value));
+
+            offset = end;
+        }
+
+        markup.push_str(&source[offset..tag.length]);
+
+        markup
+    }
+}
+
+/// Find `needle` (ASCII) in `haystack`, ignoring ASCII case.
+fn find_ignore_ascii_case(haystack: &str, needle: &str) -> Option<usize> {
+    let haystack = haystack.as_bytes();
+    let needle = needle.as_bytes();
+
+    if needle.len() > haystack.len() {
+        return None;
+    }
+
+    (0..=(haystack.len() - needle.len()))
+        .find(|&i| haystack[i..(i + needle.len())].eq_ignore_ascii_case(needle))
+}
+
+#[inline]
+fn is_name_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'-' || b == b'_' || b == b':' || b == b'.'
+}
+
+/// Parse a start tag at the beginning of `source`, which starts with `<` and a letter.
+fn parse_start_tag(source: &str) -> Option<Tag<'_>> {
+    let bytes = source.as_bytes();
+
+    let mut p = 1;
+
+    while p < bytes.len() && is_name_byte(bytes[p]) {
+        p += 1;
+    }
+
+    let name = source[1..p].to_ascii_lowercase();
+
+    let mut attributes = Vec::new();
+
+    loop {
+        while p < bytes.len() && bytes[p].is_ascii_whitespace() {
+            p += 1;
+        }
+
+        if p >= bytes.len() {
+            return None;
+        }
+
+        match bytes[p] {
+            b'>' => {
+                return Some(Tag {
+                    name,
+                    attributes,
+                    self_closing: false,
+                    length: p + 1,
+                })
+            },
+            b'/' if bytes.get(p + 1) == Some(&b'>') => {
+                return Some(Tag {
+                    name,
+                    attributes,
+                    self_closing: true,
+                    length: p + 2,
+                })
+            },
+            b'/' => {
+                p += 1;
+
+                continue;
+            },
+            _ => (),
+        }
+
+        let name_start = p;
+
+        while p < bytes.len()
+            && !bytes[p].is_ascii_whitespace()
+            && bytes[p] != b'='
+            && bytes[p] != b'>'
+            && bytes[p] != b'/'
+        {
+            p += 1;
+        }
+
+        let attribute_name = source[name_start..p].to_ascii_lowercase();
+
+        while p < bytes.len() && bytes[p].is_ascii_whitespace() {
+            p += 1;
+        }
+
+        if bytes.get(p) != Some(&b'=') {
+            attributes.push(Attribute {
+                name:        attribute_name,
+                value:       None,
+                value_range: (p, p),
+            });
+
+            continue;
+        }
+
+        p += 1;
+
+        while p < bytes.len() && bytes[p].is_ascii_whitespace() {
+            p += 1;
+        }
+
+        let (value_start, value_end) = match bytes.get(p) {
+            Some(&quote) if quote == b'"' || quote == b'\'' => {
+                let start = p + 1;
+                let end = start + source[start..].find(quote as char)?;
+
+                p = end + 1;
+
+                (start, end)
+            },
+            Some(_) => {
+                let start = p;
+
+                while p < bytes.len() && !bytes[p].is_ascii_whitespace() && bytes[p] != b'>' {
+                    p += 1;
+                }
+
+                (start, p)
+            },
+            None => return None,
+        };
+
+        attributes.push(Attribute {
+            name:        attribute_name,
+            value:       Some(&source[value_start..value_end]),
+            value_range: (value_start, value_end),
+        });
+    }
+}
+
+/// Convert an HTML (or XHTML) document or fragment, leaving the markup untouched.
+///
+/// Only text nodes and the values of the `title`, `alt` and `placeholder` attributes (plus `content` of `<meta name="description">`) are converted. The contents of `<script>`, `<style>`, `<code>` and `<pre>`, and of elements with `translate="no"`, are kept. Text which is split by inline elements such as `<b>` or `<span>` is converted as one unit, so a phrase like `<b>软</b>件` is still recognized. Character references are left as they are.
+pub fn convert_html<S: AsRef<str>>(opencc: &OpenCC, html: S) -> String {
+    let html = html.as_ref();
+
+    let mut converter = Converter {
+        opencc,
+        output: String::with_capacity(html.len()),
+        pending: Vec::new(),
+        stack: Vec::new(),
+    };
+
+    let bytes = html.as_bytes();
+
+    let mut p = 0;
+    let mut text_start = 0;
+
+    while p < bytes.len() {
+        if bytes[p] != b'<' {
+            p += 1;
+
+            continue;
+        }
+
+        let rest = &html[p..];
+
+        // the length of a comment, a doctype, a CDATA section or a processing instruction
+        let special_length = if let Some(comment) = rest.strip_prefix("<!--") {
+            Some(comment.find("-->").map(|i| i + 7).unwrap_or(rest.len()))
+        } else if rest.starts_with("<![CDATA[") {
+            Some(rest.find("]]>").map(|i| i + 3).unwrap_or(rest.len()))
+        } else if rest.starts_with("<!") || rest.starts_with("<?") {
+            Some(rest.find('>').map(|i| i + 1).unwrap_or(rest.len()))
+        } else {
+            None
+        };
+
+        if let Some(length) = special_length {
+            converter.push_text(&html[text_start..p]);
+            converter.push_markup(&rest[..length]);
+
+            p += length;
+            text_start = p;
+
+            continue;
+        }
+
+        match bytes.get(p + 1) {
+            Some(b'/') if bytes.get(p + 2).map(|b| b.is_ascii_alphabetic()).unwrap_or(false) => {
+                let length = match rest.find('>') {
+                    Some(i) => i + 1,
+                    None => {
+                        converter.push_text(&html[text_start..p]);
+                        converter.push_markup(rest);
+
+                        text_start = bytes.len();
+
+                        break;
+                    },
+                };
+
+                let name_length = rest[2..].bytes().take_while(|&b| is_name_byte(b)).count();
+                let name = rest[2..(2 + name_length)].to_ascii_lowercase();
+
+                converter.push_text(&html[text_start..p]);
+                converter.end_tag(&rest[..length], &name);
+
+                p += length;
+                text_start = p;
+            },
+            Some(b) if b.is_ascii_alphabetic() => {
+                let tag = match parse_start_tag(rest) {
+                    Some(tag) => tag,
+                    None => {
+                        converter.push_text(&html[text_start..p]);
+                        converter.push_markup(rest);
+
+                        text_start = bytes.len();
+
+                        break;
+                    },
+                };
+
+                let length = tag.length;
+                let name = tag.name.clone();
+                let self_closing = tag.self_closing;
+
+                converter.push_text(&html[text_start..p]);
+                converter.start_tag(rest, tag);
+
+                p += length;
+                text_start = p;
+
+                if RAW_TEXT_ELEMENTS.contains(&name.as_str()) && !self_closing {
+                    let end = find_ignore_ascii_case(&html[p..], &format!("</{}", name))
+                        .map(|i| p + i)
+                        .unwrap_or(bytes.len());
+
+                    converter.push_markup(&html[p..end]);
+
+                    p = end;
+                    text_start = p;
+                }
+            },
+            _ => p += 1,
+        }
+    }
+
+    converter.push_text(&html[text_start..]);
+    converter.flush();
+
+    converter.output
+}
@@ -0,0 +1,432 @@
+use std::collections::HashMap;
+
+use serde::de::IgnoredAny;
+use serde_json::{Map, Value};
+
+use crate::OpenCC;
+
+/// Options for `convert_json` and `convert_json_value`.
+///
+/// The `include` and `exclude` rules are JSONPath-style expressions, such as `$.menu.items[*].label`, `$['zh-CN']` or `$..description`. A rule selects the matched nodes together with everything inside them. Paths always refer to the keys of the input document.
+#[derive(Debug, Clone, Default)]
+pub struct JsonOptions {
+    /// Whether to convert the keys of objects, too.
+    pub convert_keys: bool,
+    /// Only the strings selected by these rules are converted. If it is empty, all strings are selected.
+    pub include:      Vec<String>,
+    /// The strings selected by these rules are not converted, even if they are included.
+    pub exclude:      Vec<String>,
+}
+
+#[derive(Debug, Clone)]
+enum PathElement {
+    Key(String),
+    Index(usize),
+}
+
+#[derive(Debug)]
+enum Selector {
+    Key(String),
+    Index(usize),
+    Wildcard,
+}
+
+impl Selector {
+    fn matches(&self, element: &PathElement) -> bool {
+        match (self, element) {
+            (Selector::Wildcard, _) => true,
+            (Selector::Key(a), PathElement::Key(b)) => a == b,
+            (Selector::Index(a), PathElement::Index(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Step {
+    descendant: bool,
+    selector:   Selector,
+}
+
+#[derive(Debug)]
+struct JsonPath(Vec<Step>);
+
+impl JsonPath {
+    fn parse(expression: &str) -> Result<JsonPath, &'static str> {
+        const ERROR: &str = "The JSONPath expression is not valid.";
+
+        let mut rest = expression.trim().strip_prefix('$').ok_or(ERROR)?;
+        let mut steps = Vec::new();
+
+        while !rest.is_empty() {
+            let descendant = rest.starts_with("..");
+
+            if descendant {
+                rest = &rest[2..];
+            } else if let Some(r) = rest.strip_prefix('.') {
+                rest = r;
+            } else if !rest.starts_with('[') {
+                return Err(ERROR);
+            }
+
+            let selector = if let Some(r) = rest.strip_prefix('[') {
+                let end = r.find(']').ok_or(ERROR)?;
+                let inner = r[..end].trim();
+
+                rest = &r[(end + 1)..];
+
+                if inner == "*" {
+                    Selector::Wildcard
+                } else if let Ok(index) = inner.parse::<usize>() {
+                    Selector::Index(index)
+                } else if inner.len() >= 2
+                    && ((inner.starts_with('\'') && inner.ends_with('\''))
+                        || (inner.starts_with('"') && inner.ends_with('"')))
+                {
+                    Selector::Key(inner[1..(inner.len() - 1)].to_string())
+                } else {
+                    return Err(ERROR);
+                }
+            } else {
+                let end = rest.find(['.', '[']).unwrap_or(rest.len());
+                let name = &rest[..end];
+
+                rest = &rest[end..];
+
+                match name {
+                    "" => return Err(ERROR),
+                    "*" => Selector::Wildcard,
+                    _ => Selector::Key(name.to_string()),
+                }
+            };
+
+            steps.push(Step {
+                descendant,
+                selector,
+            });
+        }
+
+        Ok(JsonPath(steps))
+    }
+
+    /// Whether the steps match the whole path.
+    fn matches(steps: &[Step], path: &[PathElement]) -> bool {
+        let step = match steps.first() {
+            Some(step) => step,
+            None => return path.is_empty(),
+        };
+
+        if step.descendant {
+            (0..path.len()).any(|i| {
+                step.selector.matches(&path[i]) && Self::matches(&steps[1..], &path[(i + 1)..])
+            })
+        } else {
+            !path.is_empty()
+                && step.selector.matches(&path[0])
+                && Self::matches(&steps[1..], &path[1..])
+        }
+    }
+
+    /// Whether the path or one of its ancestors is matched.
+    fn selects(&self, path: &[PathElement]) -> bool {
+        (0..=path.len()).any(|i| Self::matches(&self.0, &path[..i]))
+    }
+}
+
+struct Rules {
+    include: Vec<JsonPath>,
+    exclude: Vec<JsonPath>,
+}
+
+impl Rules {
+    fn new(options: &JsonOptions) -> Result<Rules, &'static str> {
+        Ok(Rules {
+            include: options
+                .include
+                .iter()
+                .map(|expression| JsonPath::parse(expression))
+                .collect::<Result<_, _>>()?,
+            exclude: options
+                .exclude
+                .iter()
+                .map(|expression| JsonPath::parse(expression))
+                .collect::<Result<_, _>>()?,
+        })
+    }
+
+    fn selects(&self, path: &[PathElement]) -> bool {
+        (self.include.is_empty() || self.include.iter().any(|rule| rule.selects(path)))
+            && !self.exclude.iter().any(|rule| rule.selects(path))
+    }
+}
+
+/// Encode a string as a JSON string literal. If `escape_non_ascii` is set, non-ASCII characters are written as `\uXXXX` escapes.
+fn encode_json_string(s: &str, escape_non_ascii: bool) -> String {
+    let mut output = String::with_capacity(s.len() + 2);
+
+    output.push('"');
+
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            '\u{8}' => output.push_str("\\b"),
+            '\u{c}' => output.push_str("\\f"),
+            c if c < ' ' || (escape_non_ascii && !c.is_ascii()) => {
+                let mut buffer = [0u16; 2];
+
+                for unit in c.encode_utf16(&mut buffer) {
+                    output.push_str(&format!("\\u{:04x}", unit));
+                }
+            },
+            c => output.push(c),
+        }
+    }
+
+    output.push('"');
+
+    output
+}
+
+const KEY_COLLISION: &str = "Two keys of an object would be converted to the same key.";
+
+/// Convert a JSON string literal (with quotes), keeping the way it is escaped as far as possible.
+fn convert_json_string(opencc: &OpenCC, token: &str) -> String {
+    if !token.contains('\\') {
+        // no escapes, so the conversion of CJK characters cannot break the literal
+        return opencc.convert_in_document(token);
+    }
+
+    let decoded: String = match serde_json::from_str(token) {
+        Ok(decoded) => decoded,
+        Err(_) => return token.to_string(),
+    };
+
+    let converted = opencc.convert_in_document(&decoded);
+
+    if converted == decoded {
+        token.to_string()
+    } else {
+        encode_json_string(&converted, token.contains("\\u"))
+    }
+}
+
+enum Container {
+    /// `keys` maps the keys written so far to whether they are changed by the conversion.
+    Object {
+        key:           Option<String>,
+        expecting_key: bool,
+        keys:          HashMap<String, bool>,
+    },
+    Array {
+        index: usize,
+    },
+}
+
+fn current_path(stack: &[Container]) -> Vec<PathElement> {
+    stack
+        .iter()
+        .filter_map(|container| match container {
+            Container::Object {
+                key, ..
+            } => key.clone().map(PathElement::Key),
+            Container::Array {
+                index,
+            } => Some(PathElement::Index(*index)),
+        })
+        .collect()
+}
+
+/// Convert the strings in a JSON document, keeping the order of keys and the original formatting.
+///
+/// Only string values are converted by default. Keys are converted if `options.convert_keys` is set, and an error is returned if two keys of an object would be converted to the same key (like `发` and `發`). Strings written with escapes are re-escaped only when they are changed.
+pub fn convert_json<S: AsRef<str>>(
+    opencc: &OpenCC,
+    json: S,
+    options: &JsonOptions,
+) -> Result<String, &'static str> {
+    let json = json.as_ref();
+
+    let rules = Rules::new(options)?;
+
+    serde_json::from_str::<IgnoredAny>(json).map_err(|_| "The JSON document is not valid.")?;
+
+    let bytes = json.as_bytes();
+
+    let mut output = String::with_capacity(json.len());
+    let mut stack: Vec<Container> = Vec::new();
+
+    let mut p = 0;
+    let mut copied = 0;
+
+    while p < bytes.len() {
+        match bytes[p] {
+            b'{' => stack.push(Container::Object {
+                key:           None,
+                expecting_key: true,
+                keys:          HashMap::new(),
+            }),
+            b'[' => stack.push(Container::Array {
+                index: 0
+            }),
+            b'}' | b']' => {
+                stack.pop();
+            },
+            b',' => match stack.last_mut() {
+                Some(Container::Object {
+                    expecting_key, ..
+                }) => *expecting_key = true,
+                Some(Container::Array {
+                    index,
+                }) => *index += 1,
+                None => (),
+            },
+            b':' => {
+                if let Some(Container::Object {
+                    expecting_key, ..
+                }) = stack.last_mut()
+                {
+                    *expecting_key = false;
+                }
+            },
+            b'"' => {
+                let mut end = p + 1;
+
+                while bytes[end] != b'"' {
+                    end += if bytes[end] == b'\\' { 2 } else { 1 };
+                }
+
+                let token = &json[p..=end];
+
+                let is_key = match stack.last_mut() {
+                    Some(Container::Object {
+                        key,
+                        expecting_key: true,
+                        ..
+                    }) => {
+                        *key = Some(serde_json::from_str(token).unwrap());
+
+                        true
+                    },
+                    _ => false,
+                };
+
+                let converted =
+                    if (!is_key || options.convert_keys) && rules.selects(&current_path(&stack)) {
+                        Some(convert_json_string(opencc, token))
+                    } else {
+                        None
+                    };
+
+                if let Some(Container::Object {
+                    keys, ..
+                }) = stack.last_mut().filter(|_| is_key && options.convert_keys)
+                {
+                    let (output_key, changed) = match converted.as_deref() {
+                        Some(converted) => (converted, converted != token),
+                        None => (token, false),
+                    };
+
+                    if let Some(other_changed) =
+                        keys.insert(serde_json::from_str(output_key).unwrap(), changed)
+                    {
+                        if changed || other_changed {
+                            return Err(KEY_COLLISION);
+                        }
+                    }
+                }
+
+                if let Some(converted) = converted {
+                    output.push_str(&json[copied..p]);
+                    output.push_str(&converted);
+
+                    copied = end + 1;
+                }
+
+                p = end;
+            },
+            _ => (),
+        }
+
+        p += 1;
+    }
+
+    output.push_str(&json[copied..]);
+
+    Ok(output)
+}
+
+fn convert_value_inner(
+    opencc: &OpenCC,
+    value: &mut Value,
+    options: &JsonOptions,
+    rules: &Rules,
+    path: &mut Vec<PathElement>,
+) -> Result<(), &'static str> {
+    match value {
+        Value::String(s) if rules.selects(path) => {
+            *s = opencc.convert_in_document(s.as_str());
+        },
+        Value::Array(array) => {
+            for (index, value) in array.iter_mut().enumerate() {
+                path.push(PathElement::Index(index));
+                convert_value_inner(opencc, value, options, rules, path)?;
+                path.pop();
+            }
+        },
+        Value::Object(map) => {
+            let mut converted_map = Map::new();
+
+            for (key, mut value) in std::mem::take(map) {
+                path.push(PathElement::Key(key.clone()));
+
+                convert_value_inner(opencc, &mut value, options, rules, path)?;
+
+                let key = if options.convert_keys && rules.selects(path) {
+                    opencc.convert_in_document(&key)
+                } else {
+                    key
+                };
+
+                path.pop();
+
+                // the keys of the input are unique, so a collision means that a key is changed
+                if converted_map.insert(key, value).is_some() {
+                    return Err(KEY_COLLISION);
+                }
+            }
+
+            *map = converted_map;
+        },
+        _ => (),
+    }
+
+    Ok(())
+}
+
+/// Convert the strings in a `serde_json::Value` in place. See `convert_json` and `JsonOptions`.
+///
+/// The order of keys follows `serde_json::Map`, which keeps the insertion order only if the `preserve_order` feature of `serde_json` is enabled. If two keys of an object would be converted to the same key (like `发` and `發`), an error is returned and the value is left untouched.
+pub fn convert_json_value(
+    opencc: &OpenCC,
+    value: &mut Value,
+    options: &JsonOptions,
+) -> Result<(), &'static str> {
+    let rules = Rules::new(options)?;
+
+    if options.convert_keys {
+        // a collision may be found after some strings are converted
+        let mut converted = value.clone();
+
+        convert_value_inner(opencc, &mut converted, options, &rules, &mut Vec::new())?;
+
+        *value = converted;
+
+        Ok(())
+    } else {
+        convert_value_inner(opencc, value, options, &rules, &mut Vec::new())
+    }
+}
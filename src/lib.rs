@@ -9,8 +9,11 @@ To compile this crate, you need to compile the OpenCC C++ library first. You can
 * `OPENCC_LIBS`: The library names that you want to link, like `-l`. Use `:` to separate. Typically, it contains **opencc:marisa**.
 * `OPENCC_INCLUDE_DIRS`: The directories of header files, like `-i`. Use `:` to separate.
 * `OPENCC_STATIC`: Whether to use `static` or `dylib`.
+* `OPENCC_DATA_DIR`: The data directory of OpenCC (its config files and dictionaries), if it is not `share/opencc` under `OPENCC_DIR` or the prefix of pkg-config. It is only used by `DictionaryProfiler::new`.
 * `OPENCC_DYLIB_STDCPP`: If you use `static` linking, and your OpenCC library is compiled by the GNU C, this environment variable should be set.
 
+The minimum supported Rust version is 1.61 for the default features and `static-dictionaries`. The other optional features need newer versions, like their dependencies do.
+
 ## Examples
 
 ```rust
@@ -67,6 +70,119 @@ let opencc = OpenCC::new(Path::join(&output_path, DefaultConfig::TW2SP)).unwrap(
 
 assert_eq!("凉风有讯", &opencc.convert("涼風有訊"));
 ```
+
+## Punctuation
+
+OpenCC's dictionaries don't touch punctuation. Set a punctuation stage to localize quotation marks (`“‘’”` and `「『』」`, paired by their nesting) and other region-specific punctuation after the conversion. The mapping tables of `Punctuation` are public, so they can be adjusted.
+
+```rust,ignore
+use opencc_rust::*;
+
+let mut opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+opencc.set_punctuation(DefaultConfig::S2TWP.get_punctuation_style().map(Punctuation::new));
+
+assert_eq!("「涼風有訊」", &opencc.convert("“凉风有讯”"));
+```
+
+Similarly, a width normalization stage (`OpenCC::set_width_normalization`) can fold full-width alphanumerics (`ＡＢＣ１２３`), half-width katakana, ideographic spaces and mixed-width punctuation before or after the conversion. `WidthNormalization::new` creates the policy of Chinese text, which is the same in mainland China, Taiwan and Hong Kong, and its fields can be changed for other policies. `WidthNormalization::apply_with_offsets` tells where each output character comes from. The converters of documents (CSV, JSON, YAML, source code and so on) only fold full-width alphanumerics and half-width katakana, and the punctuation stage keeps ASCII characters in them, so that the syntax of the documents is never changed.
+
+Text extracted from PDFs or legacy systems may contain CJK compatibility ideographs (like `說`, U+F96F) and radicals (like `⾔` and `⻌`), which never match the dictionaries. Enable the `unicode-normalization` feature and set a Unicode normalization stage (`OpenCC::set_unicode_normalization`) to compose the text to NFC and fold them to unified ideographs before the conversion. Variation selectors are kept, and `UnicodeNormalization::apply_with_report` tells which characters are normalized.
+
+Byte streams from legacy systems may not be valid UTF-8. The `bytes` feature adds `OpenCC::convert_bytes`, which converts the valid UTF-8 runs of a byte slice, and, as set by `InvalidUtf8Policy`, either copies the invalid bytes to the output as they are or returns their byte ranges in a `ConvertBytesError`.
+
+## Serde
+
+Enable the `serde` feature to convert string fields while they are serialized or deserialized, with the shared instance of a default config. The `opencc_rust::serde` module has a module for each default config (like `s2twp`), for the `with`, `serialize_with` and `deserialize_with` attributes, and their `option` modules are for `Option<String>` fields.
+
+```rust,ignore
+#[derive(Serialize, Deserialize)]
+struct Product {
+    #[serde(serialize_with = "opencc_rust::serde::s2twp::serialize")]
+    name:        String,
+    #[serde(with = "opencc_rust::serde::s2twp::option")]
+    description: Option<String>,
+}
+```
+
+## Change Reports
+
+Enable the `diff` feature to audit a conversion. `OpenCC::convert_with_changes` returns the converted string with a list of `Change`s, each of which has the byte ranges of the source and the converted string and the text before and after. `find_changes` compares any two strings, such as a document and its format-aware conversion.
+
+`round_trip_check` converts a text with a default config and back with its inverse (`DefaultConfig::inverse`), and reports every span which does not return to the original, classified as a character merge, a phrase idiom or a variant. It helps to decide whether a document can be stored in one script and generated in the other. A `RoundTripChecker` loads the two configs once (or takes two `OpenCC` instances) to check many texts.
+
+The `statistics` feature adds `DictionaryProfiler`, which converts text with a default config and counts how many segments each dictionary of the conversion chain (like `STPhrases` and `STCharacters`) converted, and which CJK ideographs no dictionary changed, per call and accumulated. OpenCC does not expose its matches, so an ideograph which a dictionary maps to itself (like `有`) also counts as unchanged.
+
+## Performance
+
+An `OpenCC` instance can be shared by threads, and `OpenCC::shared` returns a process-wide `Arc<OpenCC>` for a default config, which is opened once on first use (or ahead of time with `OpenCC::preload_shared`), so services do not load their own copies of the dictionaries. `OpenCC::set_shared_dictionary_directory` makes the shared instances load another directory, which, with the `static-dictionaries` feature, is filled with the embedded dictionaries.
+
+For bulk jobs, the `rayon` feature adds `OpenCC::convert_batch`, which converts many strings in parallel, and `OpenCC::par_convert`, which splits a large text after line breaks or sentence-ending punctuation marks and converts the pieces in parallel.
+
+For tokio services, the `tokio` feature adds `ConvertAsyncReader` and `ConvertAsyncWriter`, which stream-convert an `AsyncRead` or an `AsyncWrite` at line and sentence boundaries, and `OpenCC::convert_async`. The conversions run on the blocking threads of tokio, so the runtime is not blocked, and the punctuation and width normalization stages carry their state across the pieces of a stream.
+
+`OpenCC::convert_cow` returns a `Cow<str>` which borrows the input when nothing is changed. A string without CJK characters is not passed to OpenCC at all.
+
+To convert the same short strings (like menu labels) over and over, enable the `cache` feature and wrap the instance in a `CachedOpenCC`, which has a bounded LRU cache with a configurable capacity and maximum key length, hit/miss metrics, and `clear` for when the dictionaries change.
+
+```rust,ignore
+use opencc_rust::*;
+
+let opencc = CachedOpenCC::new(OpenCC::new(DefaultConfig::S2TWP).unwrap(), CacheOptions::default());
+
+assert_eq!("涼風有訊", &opencc.convert("凉风有讯"));
+assert_eq!("涼風有訊", &opencc.convert("凉风有讯"));
+
+assert_eq!(1, opencc.metrics().hits);
+```
+
+## Document Formats
+
+Converting a whole document with `OpenCC::convert` would also touch the parts which are not prose. The following functions convert only the text of a document and keep everything else as it is.
+
+* `convert_html`: HTML and XHTML. Only text nodes and a few attributes (`title`, `alt`, `placeholder`, and the meta description) are converted. `<script>`, `<style>`, `<code>`, `<pre>` and elements with `translate="no"` are skipped, and a phrase split by inline elements (like `<b>软</b>件`) is still converted as a whole.
+* `convert_markdown` (the `markdown` feature): Markdown. Prose, headings, table cells and link text are converted. Code spans, code blocks, HTML, URLs and link reference labels are kept byte-for-byte, and so is the original formatting. The values of a YAML or TOML front matter block are converted, but not its keys.
+* `convert_json` and `convert_json_value` (the `json` feature): JSON documents and `serde_json::Value`s. String values are converted, and optionally keys, selected by JSONPath-style include/exclude rules. The order of keys and the formatting are kept. If two keys of an object would be converted to the same key, an error is returned.
+* `convert_yaml` and `convert_toml`: YAML and TOML resource files, such as Rails-style `zh-CN.yml` locale files. Scalar string values are converted, and keys, anchors, comments and the formatting are kept. `ResourceOptions::locale_key` renames a top-level locale key, like `zh-CN:` to `zh-TW:`.
+* `convert_po`: Gettext PO and POT catalogs. Only `msgstr` (and, optionally, translator comments) is converted, never `msgid` or `msgctxt`. `PoOptions` can also set the `Language:` header and add a flag like `fuzzy` to the changed entries.
+* `convert_srt`, `convert_vtt` and `convert_ass`: SRT, WebVTT and ASS/SSA subtitles. Only the dialogue text is converted. Cue numbers, timestamps, cue settings and tags (like `{\i1}` and `\N`) are kept, and so are the line endings and the BOM.
+* `convert_epub` (the `epub` feature): EPUB e-books. The XHTML content documents, the navigation titles, and the title and description of the book are converted, and `dc:language` can be updated, such as to `DefaultConfig::get_language_tag`. Images and stylesheets are copied as they are.
+* `convert_docx` and `convert_odt` (the `office` feature): Word and OpenDocument Text documents. The text of a paragraph is converted across its runs, so a phrase split by formatting is still recognized, and the result is put back into the original runs. Styles, comments and tracked changes are kept.
+* `convert_source`: source code in Rust, C/C++, Java, JavaScript/TypeScript, Python or Go. Only comments and string literals (or, with `SourceOptions::comments_only`, only comments) are converted, never identifiers, not even those embedded in template literals, f-strings and Rust format strings.
+* `convert_csv`: CSV and TSV streams, record by record. Only the columns selected by header name or index are converted, and quoted fields with delimiters, quotes and line breaks are handled.
+
+The `Format` enum picks one of these by a file extension, and `Format::convert` converts a document with the default options.
+
+## Directory Conversion
+
+Enable the `directory` feature to convert whole directory trees. The `convert_directory` function walks a directory, converts the text files which match the include/exclude globs with worker threads sharing one `OpenCC` instance, and writes them to a mirror tree or in place. With `format_aware`, each file is converted by the format of its extension, and the feature enables `markdown` and `json` for that.
+
+```rust,ignore
+use opencc_rust::*;
+
+let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+let summary = convert_directory(&opencc, "docs", &DirectoryOptions {
+    include: vec!["*.md".to_string()],
+    exclude: vec!["vendor".to_string()],
+    output: Some("docs-zh-TW".into()),
+    ..DirectoryOptions::default()
+})
+.unwrap();
+
+println!("{} files changed", summary.changed.len());
+```
+
+Set `convert_names` to rename the matched files and the directories which contain them through the same config as well. Name collisions are detected before anything is written, and `dry_run` only reports what would be done.
+
+The `cli` feature builds the `opencc-rust` command, which converts stdin, a file or a directory. With `--diff`, it prints what the conversion changes as a unified diff (or, with `--diff=json`, as a JSON change list) instead. EPUB, DOCX and ODT files are converted as documents, and the `dc:language` of an EPUB file is set to the target of the default config, or to `--language`.
+
+```bash
+opencc-rust -c s2twp --include '*.md' --exclude vendor -o docs-zh-TW docs
+opencc-rust -c s2twp --diff contract.txt
+opencc-rust -c s2twp --diff=json strings.po
+opencc-rust -c s2twp -o book-zh-TW.epub book.epub
+```
 */
 
 #[cfg(feature = "static-dictionaries")]
@@ -77,16 +193,96 @@ extern crate lazy_static;
 #[macro_use]
 extern crate lazy_static_include;
 
+#[cfg(any(feature = "epub", feature = "office"))]
+mod archive;
+#[cfg(feature = "tokio")]
+mod asynchronous;
+#[cfg(feature = "bytes")]
+mod bytes;
+#[cfg(feature = "cache")]
+mod cache;
+#[cfg(feature = "diff")]
+mod changes;
+mod csv;
+#[cfg(feature = "directory")]
+mod directory;
+#[cfg(feature = "epub")]
+mod epub;
+mod format;
+mod html;
+#[cfg(feature = "json")]
+mod json;
+#[cfg(feature = "markdown")]
+mod markdown;
+#[cfg(feature = "office")]
+mod office;
+#[cfg(feature = "rayon")]
+mod parallel;
+mod po;
+mod punctuation;
+mod resource;
+#[cfg(feature = "diff")]
+mod round_trip;
+mod segments;
+#[cfg(feature = "serde")]
+pub mod serde;
+mod shared;
+mod source;
+#[cfg(feature = "statistics")]
+mod statistics;
+mod subtitles;
+#[cfg(feature = "unicode-normalization")]
+mod unicode;
+mod width;
+#[cfg(feature = "office")]
+mod xml;
+
 #[cfg(feature = "static-dictionaries")]
 use std::fs::{self, File};
 #[cfg(feature = "static-dictionaries")]
 use std::io::Write;
 use std::{
+    borrow::Cow,
     ffi::{CStr, CString},
     path::Path,
+    str::FromStr,
+    string::FromUtf8Error,
 };
 
+#[cfg(feature = "tokio")]
+pub use asynchronous::*;
+#[cfg(feature = "bytes")]
+pub use bytes::*;
+#[cfg(feature = "cache")]
+pub use cache::*;
+#[cfg(feature = "diff")]
+pub use changes::*;
+pub use csv::*;
+#[cfg(feature = "directory")]
+pub use directory::*;
+#[cfg(feature = "epub")]
+pub use epub::*;
+pub use format::*;
+pub use html::*;
+#[cfg(feature = "json")]
+pub use json::*;
 use libc::{c_char, c_int, c_void, size_t};
+#[cfg(feature = "markdown")]
+pub use markdown::*;
+#[cfg(feature = "office")]
+pub use office::*;
+pub use po::*;
+pub use punctuation::*;
+pub use resource::*;
+#[cfg(feature = "diff")]
+pub use round_trip::*;
+pub use source::*;
+#[cfg(feature = "statistics")]
+pub use statistics::*;
+pub use subtitles::*;
+#[cfg(feature = "unicode-normalization")]
+pub use unicode::*;
+pub use width::*;
 
 #[link(name = "opencc")]
 extern "C" {
@@ -187,7 +383,7 @@ new_sd_instance!(TWVARIANTS_REV_OCD, "TWVariantsRev.ocd2");
 new_sd_instance!(TWVARIANTS_REV_PHRASES_OCD, "TWVariantsRevPhrases.ocd2");
 
 /// Default configs.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
 pub enum DefaultConfig {
     /// Traditional Chinese (Hong Kong Standard) to Simplified Chinese
     HK2S,
@@ -239,6 +435,90 @@ impl DefaultConfig {
             DefaultConfig::TW2T => "tw2t.json",
         }
     }
+
+    /// Get the punctuation conventions of the region this default config converts to, if it targets a region. See `OpenCC::set_punctuation`.
+    pub fn get_punctuation_style(self) -> Option<PunctuationStyle> {
+        match self {
+            DefaultConfig::S2TW | DefaultConfig::S2TWP | DefaultConfig::T2TW => {
+                Some(PunctuationStyle::Taiwan)
+            },
+            DefaultConfig::S2HK | DefaultConfig::T2HK => Some(PunctuationStyle::HongKong),
+            DefaultConfig::HK2S
+            | DefaultConfig::T2S
+            | DefaultConfig::TW2S
+            | DefaultConfig::TW2SP => Some(PunctuationStyle::Mainland),
+            DefaultConfig::HK2T
+            | DefaultConfig::JP2T
+            | DefaultConfig::S2T
+            | DefaultConfig::T2JP
+            | DefaultConfig::TW2T => None,
+        }
+    }
+
+    /// Get the BCP 47 language tag of the text this default config converts to, such as `zh-TW` for `S2TWP`, for metadata like `dc:language` (see `EpubOptions`).
+    pub fn get_language_tag(self) -> &'static str {
+        match self {
+            DefaultConfig::S2TW | DefaultConfig::S2TWP | DefaultConfig::T2TW => "zh-TW",
+            DefaultConfig::S2HK | DefaultConfig::T2HK => "zh-HK",
+            DefaultConfig::HK2S
+            | DefaultConfig::T2S
+            | DefaultConfig::TW2S
+            | DefaultConfig::TW2SP => "zh-CN",
+            DefaultConfig::HK2T
+            | DefaultConfig::JP2T
+            | DefaultConfig::S2T
+            | DefaultConfig::TW2T => "zh-Hant",
+            DefaultConfig::T2JP => "ja",
+        }
+    }
+
+    /// Get the default config which converts the other way around, such as `TW2SP` for `S2TWP`. A round trip through the two is not always lossless (see `round_trip_check`).
+    pub fn inverse(self) -> DefaultConfig {
+        match self {
+            DefaultConfig::HK2S => DefaultConfig::S2HK,
+            DefaultConfig::HK2T => DefaultConfig::T2HK,
+            DefaultConfig::JP2T => DefaultConfig::T2JP,
+            DefaultConfig::S2HK => DefaultConfig::HK2S,
+            DefaultConfig::S2T => DefaultConfig::T2S,
+            DefaultConfig::S2TW => DefaultConfig::TW2S,
+            DefaultConfig::S2TWP => DefaultConfig::TW2SP,
+            DefaultConfig::T2HK => DefaultConfig::HK2T,
+            DefaultConfig::T2JP => DefaultConfig::JP2T,
+            DefaultConfig::T2S => DefaultConfig::S2T,
+            DefaultConfig::T2TW => DefaultConfig::TW2T,
+            DefaultConfig::TW2S => DefaultConfig::S2TW,
+            DefaultConfig::TW2SP => DefaultConfig::S2TWP,
+            DefaultConfig::TW2T => DefaultConfig::T2TW,
+        }
+    }
+}
+
+impl FromStr for DefaultConfig {
+    type Err = &'static str;
+
+    /// Parse a config name such as `s2twp` or `s2twp.json`. The name is case-insensitive.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_lowercase();
+        let name = s.strip_suffix(".json").unwrap_or(&s);
+
+        match name {
+            "hk2s" => Ok(DefaultConfig::HK2S),
+            "hk2t" => Ok(DefaultConfig::HK2T),
+            "jp2t" => Ok(DefaultConfig::JP2T),
+            "s2hk" => Ok(DefaultConfig::S2HK),
+            "s2t" => Ok(DefaultConfig::S2T),
+            "s2tw" => Ok(DefaultConfig::S2TW),
+            "s2twp" => Ok(DefaultConfig::S2TWP),
+            "t2hk" => Ok(DefaultConfig::T2HK),
+            "t2jp" => Ok(DefaultConfig::T2JP),
+            "t2s" => Ok(DefaultConfig::T2S),
+            "t2tw" => Ok(DefaultConfig::T2TW),
+            "tw2s" => Ok(DefaultConfig::TW2S),
+            "tw2sp" => Ok(DefaultConfig::TW2SP),
+            "tw2t" => Ok(DefaultConfig::TW2T),
+            _ => Err("Unknown default config."),
+        }
+    }
 }
 
 impl AsRef<Path> for DefaultConfig {
@@ -255,7 +535,11 @@ impl AsRef<str> for DefaultConfig {
 
 /// OpenCC binding for Rust.
 pub struct OpenCC {
-    opencc: *mut c_void,
+    opencc:      *mut c_void,
+    punctuation: Option<Punctuation>,
+    width:       Option<WidthNormalization>,
+    #[cfg(feature = "unicode-normalization")]
+    unicode:     Option<UnicodeNormalization>,
 }
 
 unsafe impl Send for OpenCC {}
@@ -265,8 +549,23 @@ unsafe impl Sync for OpenCC {}
 impl OpenCC {
     /// Create a new OpenCC instance through a file provided by its path.
     pub fn new<P: AsRef<Path>>(config_file_path: P) -> Result<OpenCC, &'static str> {
+        let config_file_path = config_file_path.as_ref().as_os_str();
+
+        #[cfg(unix)]
+        let config_file_path = {
+            use std::os::unix::ffi::OsStrExt;
+
+            CString::new(config_file_path.as_bytes())
+        };
+
+        #[cfg(not(unix))]
+        let config_file_path = match config_file_path.to_str() {
+            Some(config_file_path) => CString::new(config_file_path),
+            None => return Err("The config file path is not valid UTF-8."),
+        };
+
         let config_file_path =
-            CString::new(config_file_path.as_ref().as_os_str().to_str().unwrap()).unwrap();
+            config_file_path.map_err(|_| "The config file path contains a NUL byte.")?;
 
         let opencc = unsafe { opencc_open(config_file_path.as_ptr()) };
 
@@ -277,19 +576,153 @@ impl OpenCC {
 
         Ok(OpenCC {
             opencc,
+            punctuation: None,
+            width: None,
+            #[cfg(feature = "unicode-normalization")]
+            unicode: None,
         })
     }
 
-    /// Convert a string to another string.
-    pub fn convert<S: AsRef<str>>(&self, input: S) -> String {
-        let input = input.as_ref();
+    /// Set the punctuation stage, which runs after the conversion of the dictionaries, or `None` to remove it.
+    ///
+    /// ```rust,ignore
+    /// let mut opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+    ///
+    /// opencc.set_punctuation(DefaultConfig::S2TWP.get_punctuation_style().map(Punctuation::new));
+    ///
+    /// assert_eq!("「涼風有訊」", &opencc.convert("“凉风有讯”"));
+    /// ```
+    #[inline]
+    pub fn set_punctuation(&mut self, punctuation: Option<Punctuation>) {
+        self.punctuation = punctuation;
+    }
+
+    /// Get the punctuation stage.
+    #[inline]
+    pub fn get_punctuation(&self) -> Option<&Punctuation> {
+        self.punctuation.as_ref()
+    }
+
+    /// Set the width normalization stage, which runs before or after the conversion of the dictionaries (see `WidthNormalization::before_conversion`), or `None` to remove it.
+    #[inline]
+    pub fn set_width_normalization(&mut self, width: Option<WidthNormalization>) {
+        self.width = width;
+    }
+
+    /// Get the width normalization stage.
+    #[inline]
+    pub fn get_width_normalization(&self) -> Option<&WidthNormalization> {
+        self.width.as_ref()
+    }
+
+    /// Set the Unicode normalization stage, which runs before the conversion of the dictionaries (and before the width normalization stage), or `None` to remove it.
+    #[cfg(feature = "unicode-normalization")]
+    #[inline]
+    pub fn set_unicode_normalization(&mut self, unicode: Option<UnicodeNormalization>) {
+        self.unicode = unicode;
+    }
+
+    /// Get the Unicode normalization stage.
+    #[cfg(feature = "unicode-normalization")]
+    #[inline]
+    pub fn get_unicode_normalization(&self) -> Option<&UnicodeNormalization> {
+        self.unicode.as_ref()
+    }
+
+    /// Apply the stages which run before the conversion of the dictionaries. See `convert_in_document` for `in_document`.
+    fn pre_process<'a>(&self, input: &'a str, in_document: bool) -> Cow<'a, str> {
+        #[cfg(feature = "unicode-normalization")]
+        let input = match self.unicode.as_ref() {
+            Some(unicode) => Cow::Owned(unicode.apply(input)),
+            None => Cow::Borrowed(input),
+        };
+
+        #[cfg(not(feature = "unicode-normalization"))]
+        let input = Cow::Borrowed(input);
 
+        match self.width.as_ref() {
+            Some(width) if width.before_conversion => Cow::Owned(if in_document {
+                width.apply_in_document(&input)
+            } else {
+                width.apply(input)
+            }),
+            _ => input,
+        }
+    }
+
+    /// Apply the stages which run after the conversion of the dictionaries. See `convert_in_document` for `in_document`.
+    fn post_process(&self, converted: String, in_document: bool) -> String {
+        let converted = match self.punctuation.as_ref() {
+            Some(punctuation) if in_document => punctuation.apply_in_document(&converted),
+            Some(punctuation) => punctuation.apply(converted),
+            None => converted,
+        };
+
+        match self.width.as_ref() {
+            Some(width) if !width.before_conversion => {
+                if in_document {
+                    width.apply_in_document(&converted)
+                } else {
+                    width.apply(converted)
+                }
+            },
+            _ => converted,
+        }
+    }
+
+    /// Whether any stage other than the dictionaries is set.
+    #[inline]
+    fn has_stages(&self) -> bool {
+        #[cfg(feature = "unicode-normalization")]
+        if self.unicode.is_some() {
+            return true;
+        }
+
+        self.punctuation.is_some() || self.width.is_some()
+    }
+
+    #[inline]
+    fn has_post_processing(&self) -> bool {
+        self.punctuation.is_some()
+            || self.width.as_ref().map(|width| !width.before_conversion).unwrap_or(false)
+    }
+
+    /// Convert a string with the dictionaries only, without the other stages. If the output of OpenCC is not valid UTF-8, which only happens with a broken dictionary, the invalid sequences are replaced with `U+FFFD`.
+    fn convert_with_dictionaries(&self, input: &str) -> String {
+        self.try_convert_with_dictionaries(input)
+            .unwrap_or_else(|error| String::from_utf8_lossy(error.as_bytes()).into_owned())
+    }
+
+    /// Convert a string with the dictionaries only, without the other stages, or fail if the output of OpenCC is not valid UTF-8.
+    fn try_convert_with_dictionaries(&self, input: &str) -> Result<String, FromUtf8Error> {
+        if !input.contains('\0') {
+            return self.convert_c_string(input);
+        }
+
+        // OpenCC takes C strings, which cannot contain NUL, so the parts between NUL characters are converted on their own
+        let mut output = String::with_capacity(input.len());
+
+        for (i, part) in input.split('\0').enumerate() {
+            if i > 0 {
+                output.push('\0');
+            }
+
+            if !part.is_empty() {
+                output.push_str(&self.convert_c_string(part)?);
+            }
+        }
+
+        Ok(output)
+    }
+
+    /// Convert a string without NUL characters with the dictionaries.
+    fn convert_c_string(&self, input: &str) -> Result<String, FromUtf8Error> {
         let length = input.len();
         let input = CString::new(input).unwrap();
 
         let result_ptr = unsafe { opencc_convert_utf8(self.opencc, input.as_ptr(), length) };
         let result_cstr = unsafe { CStr::from_ptr(result_ptr) };
-        let result = result_cstr.to_string_lossy().to_string();
+        let result = String::from_utf8(result_cstr.to_bytes().to_vec());
 
         unsafe {
             opencc_convert_utf8_free(result_ptr);
@@ -298,12 +731,85 @@ impl OpenCC {
         result
     }
 
+    /// Convert a string to another string.
+    ///
+    /// The document converters (like `convert_csv` and `convert_json`) apply the width normalization and punctuation stages only partly, so that they cannot add or remove the syntax of a document, such as turning `，` into the delimiter `,` of a CSV field.
+    ///
+    /// If the output of OpenCC is not valid UTF-8, which only happens with a broken dictionary, the invalid sequences are replaced with `U+FFFD`. `convert_bytes` (the `bytes` feature) returns an error instead.
+    pub fn convert<S: AsRef<str>>(&self, input: S) -> String {
+        let input = self.pre_process(input.as_ref(), false);
+
+        self.post_process(self.convert_with_dictionaries(&input), false)
+    }
+
+    /// Convert a string to another string, or fail if the output of OpenCC is not valid UTF-8.
+    #[cfg(feature = "bytes")]
+    pub(crate) fn try_convert(&self, input: &str) -> Result<String, FromUtf8Error> {
+        let input = self.pre_process(input, false);
+
+        Ok(self.post_process(self.try_convert_with_dictionaries(&input)?, false))
+    }
+
+    /// Convert the text of a document, which may still contain its syntax. The width normalization stage only folds full-width letters and digits to half width and half-width katakana to full width, and the punctuation stage does not map ASCII characters or map to them.
+    pub(crate) fn convert_in_document(&self, input: &str) -> String {
+        let input = self.pre_process(input, true);
+
+        self.post_process(self.convert_with_dictionaries(&input), true)
+    }
+
+    /// Convert a string, and borrow it if nothing is changed.
+    ///
+    /// Unless a stage other than the dictionaries is set, a string without CJK characters is not passed to OpenCC at all.
+    ///
+    /// ```rust,ignore
+    /// let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+    ///
+    /// assert!(matches!(opencc.convert_cow("Hello"), Cow::Borrowed("Hello")));
+    /// assert!(matches!(opencc.convert_cow("涼風有訊"), Cow::Borrowed(_)));
+    /// assert_eq!("涼風有訊", opencc.convert_cow("凉风有讯"));
+    /// ```
+    pub fn convert_cow<'a>(&self, input: &'a str) -> Cow<'a, str> {
+        if !self.has_stages() && !input.chars().any(|c| c >= '\u{2E80}') {
+            return Cow::Borrowed(input);
+        }
+
+        let converted = self.convert(input);
+
+        if converted == input {
+            Cow::Borrowed(input)
+        } else {
+            Cow::Owned(converted)
+        }
+    }
+
+    /// Convert a string to another string, and also return what the conversion changed. See `find_changes`.
+    ///
+    /// ```rust,ignore
+    /// let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+    ///
+    /// let (converted, changes) = opencc.convert_with_changes("凉风有讯");
+    ///
+    /// assert_eq!("涼風有訊", &converted);
+    /// assert_eq!(2, changes.len()); // `凉风` and `讯`
+    /// ```
+    #[cfg(feature = "diff")]
+    pub fn convert_with_changes<S: AsRef<str>>(&self, input: S) -> (String, Vec<Change>) {
+        let input = input.as_ref();
+
+        let converted = self.convert(input);
+        let changes = find_changes(input, &converted);
+
+        (converted, changes)
+    }
+
     /// Convert a string to another string and store into a buffer.
+    ///
+    /// The stages only see `input`, not what is already in the buffer, so the punctuation stage pairs the quotation marks of `input` on their own, starting outside any quotation.
     pub fn convert_to_buffer<S: AsRef<str>>(&self, input: S, output: String) -> String {
-        let input = input.as_ref();
+        let input = self.pre_process(input.as_ref(), false);
 
         let length = input.len();
-        let input = CString::new(input).unwrap();
+        let input = CString::new(input.as_ref()).unwrap();
 
         let mut output = output.into_bytes();
         let o_len = output.len();
@@ -320,7 +826,15 @@ impl OpenCC {
             output.set_len(o_len + size);
         }
 
-        unsafe { String::from_utf8_unchecked(output) }
+        let mut output = unsafe { String::from_utf8_unchecked(output) };
+
+        if self.has_post_processing() {
+            let converted = output.split_off(o_len);
+
+            output.push_str(&self.post_process(converted, false));
+        }
+
+        output
     }
 }
 
@@ -0,0 +1,160 @@
+use std::ops::Range;
+
+use pulldown_cmark::{Event, LinkType, MetadataBlockKind, Options, Parser, Tag, TagEnd};
+
+use crate::{
+    convert_toml, convert_yaml,
+    segments::{apply_replacements, convert_segments},
+    OpenCC, ResourceOptions,
+};
+
+struct Converter<'a, 'b> {
+    opencc:       &'b OpenCC,
+    source:       &'a str,
+    /// Ranges of text which are converted as one unit.
+    pending:      Vec<Range<usize>>,
+    replacements: Vec<(Range<usize>, String)>,
+}
+
+impl<'a, 'b> Converter<'a, 'b> {
+    fn flush(&mut self) {
+        let segments: Vec<&str> =
+            self.pending.iter().map(|range| &self.source[range.clone()]).collect();
+
+        let converted = convert_segments(self.opencc, &segments);
+
+        for (range, converted) in self.pending.drain(..).zip(converted) {
+            if self.source[range.clone()] != converted {
+                self.replacements.push((range, converted));
+            }
+        }
+    }
+
+    /// Convert the values of a YAML or TOML front matter block, keeping its keys.
+    fn front_matter(&mut self, kind: MetadataBlockKind, range: Range<usize>) {
+        let source = &self.source[range.clone()];
+
+        let converted = match kind {
+            MetadataBlockKind::YamlStyle => {
+                convert_yaml(self.opencc, source, &ResourceOptions::default())
+            },
+            MetadataBlockKind::PlusesStyle => {
+                convert_toml(self.opencc, source, &ResourceOptions::default())
+            },
+        };
+
+        if source != converted {
+            self.replacements.push((range, converted));
+        }
+    }
+}
+
+/// Whether the text of a link or an image is also its reference label (or its URL), and therefore must not be converted.
+#[inline]
+fn is_label_link(link_type: LinkType) -> bool {
+    !matches!(link_type, LinkType::Inline | LinkType::Reference | LinkType::ReferenceUnknown)
+}
+
+/// Convert a Markdown (CommonMark with tables, footnotes, strikethrough and task lists) document, keeping its formatting.
+///
+/// Only prose is converted, such as paragraphs, headings, list items, table cells, and the text of links and images. Code spans, code blocks, HTML, URLs and link reference labels are kept byte-for-byte, and so is all the Markdown syntax. The values of a YAML (`---`) or TOML (`+++`) front matter block are converted like `convert_yaml` and `convert_toml` do, and its keys are kept. Text which is split by emphasis or line breaks is converted as one unit.
+pub fn convert_markdown<S: AsRef<str>>(opencc: &OpenCC, markdown: S) -> String {
+    let markdown = markdown.as_ref();
+
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_YAML_STYLE_METADATA_BLOCKS
+        | Options::ENABLE_PLUSES_DELIMITED_METADATA_BLOCKS;
+
+    let mut converter = Converter {
+        opencc,
+        source: markdown,
+        pending: Vec::new(),
+        replacements: Vec::new(),
+    };
+
+    // whether each open tag (and therefore everything inside it) is skipped
+    let mut skip_stack: Vec<bool> = Vec::new();
+    // the front matter is converted as a whole when it ends
+    let mut in_front_matter = false;
+    let mut front_matter: Option<Range<usize>> = None;
+
+    for (event, range) in Parser::new_ext(markdown, options).into_offset_iter() {
+        let skipped = skip_stack.last().copied().unwrap_or(false);
+
+        match event {
+            Event::Start(tag) => {
+                let (inline, skip) = match tag {
+                    Tag::Emphasis
+                    | Tag::Strong
+                    | Tag::Strikethrough
+                    | Tag::Superscript
+                    | Tag::Subscript => (true, false),
+                    Tag::Link {
+                        link_type, ..
+                    }
+                    | Tag::Image {
+                        link_type, ..
+                    } => (!is_label_link(link_type), is_label_link(link_type)),
+                    Tag::CodeBlock(_) | Tag::HtmlBlock => (false, true),
+                    Tag::MetadataBlock(_) => {
+                        in_front_matter = true;
+
+                        (false, true)
+                    },
+                    _ => (false, false),
+                };
+
+                if !inline {
+                    converter.flush();
+                }
+
+                skip_stack.push(skipped || skip);
+            },
+            Event::End(tag) => {
+                let inline = matches!(
+                    tag,
+                    TagEnd::Emphasis
+                        | TagEnd::Strong
+                        | TagEnd::Strikethrough
+                        | TagEnd::Superscript
+                        | TagEnd::Subscript
+                        | TagEnd::Link
+                        | TagEnd::Image
+                );
+
+                if !inline {
+                    converter.flush();
+                }
+
+                if let TagEnd::MetadataBlock(kind) = tag {
+                    in_front_matter = false;
+
+                    if let Some(range) = front_matter.take() {
+                        converter.front_matter(kind, range);
+                    }
+                }
+
+                skip_stack.pop();
+            },
+            Event::Text(_) => {
+                if in_front_matter {
+                    front_matter = Some(match front_matter.take() {
+                        Some(front_matter) => front_matter.start..range.end,
+                        None => range,
+                    });
+                } else if !skipped {
+                    converter.pending.push(range);
+                }
+            },
+            Event::SoftBreak | Event::HardBreak | Event::TaskListMarker(_) => (),
+            _ => converter.flush(),
+        }
+    }
+
+    converter.flush();
+
+    apply_replacements(markdown, converter.replacements)
+}
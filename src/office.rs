@@ -0,0 +1,147 @@
+use std::{io, ops::Range, path::Path};
+
+use crate::{
+    archive::convert_archive,
+    segments::{apply_replacements, convert_segments},
+    xml::{XmlToken, XmlTokenizer},
+    OpenCC,
+};
+
+/// The elements of a document format which matter for the conversion.
+struct Dialect {
+    /// Elements whose text is converted as one unit.
+    paragraphs: &'static [&'static str],
+    /// If it is set, only the text in this element is converted. Otherwise, all the text in paragraphs is converted.
+    text:       Option<&'static str>,
+    /// Elements whose contents are never converted.
+    skipped:    &'static [&'static str],
+}
+
+const DOCX: Dialect = Dialect {
+    paragraphs: &["w:p"],
+    // deleted text (`w:delText`) and field codes (`w:instrText`) are not in `w:t`
+    text:       Some("w:t"),
+    skipped:    &["w:del"],
+};
+
+const ODF: Dialect = Dialect {
+    paragraphs: &["text:p", "text:h"],
+    text:       None,
+    skipped:    &["office:annotation", "text:tracked-changes", "text:note-citation"],
+};
+
+fn convert_xml(opencc: &OpenCC, xml: &str, dialect: &Dialect) -> String {
+    let mut replacements = Vec::new();
+    let mut segments: Vec<Range<usize>> = Vec::new();
+
+    let mut flush = |segments: &mut Vec<Range<usize>>| {
+        let texts: Vec<&str> = segments.iter().map(|range| &xml[range.clone()]).collect();
+
+        let converted = convert_segments(opencc, &texts);
+
+        for (range, converted) in segments.drain(..).zip(converted) {
+            if xml[range.clone()] != converted {
+                replacements.push((range, converted));
+            }
+        }
+    };
+
+    let mut skip_depth = 0usize;
+    let mut paragraph_depth = 0usize;
+    let mut in_text = false;
+
+    for token in XmlTokenizer::new(xml) {
+        match token {
+            XmlToken::Start {
+                self_closing: true, ..
+            }
+            | XmlToken::Other => (),
+            XmlToken::Start {
+                name, ..
+            } => {
+                if dialect.skipped.contains(&name) {
+                    skip_depth += 1;
+                } else if skip_depth == 0 {
+                    if dialect.paragraphs.contains(&name) {
+                        flush(&mut segments);
+
+                        paragraph_depth += 1;
+                    }
+
+                    if dialect.text == Some(name) {
+                        in_text = true;
+                    }
+                }
+            },
+            XmlToken::End {
+                name,
+            } => {
+                if dialect.skipped.contains(&name) {
+                    skip_depth = skip_depth.saturating_sub(1);
+                } else if skip_depth == 0 {
+                    if dialect.paragraphs.contains(&name) {
+                        flush(&mut segments);
+
+                        paragraph_depth = paragraph_depth.saturating_sub(1);
+                    }
+
+                    if dialect.text == Some(name) {
+                        in_text = false;
+                    }
+                }
+            },
+            XmlToken::Text(range) => {
+                let selected = match dialect.text {
+                    Some(_) => in_text,
+                    None => paragraph_depth > 0,
+                };
+
+                if skip_depth == 0 && selected {
+                    segments.push(range);
+                }
+            },
+        }
+    }
+
+    flush(&mut segments);
+
+    apply_replacements(xml, replacements)
+}
+
+/// Convert a Word (DOCX) document and write the result to `output`, which may be `input` itself (the result replaces it when it is complete).
+///
+/// The text of the body, headers, footers, footnotes and endnotes is converted paragraph by paragraph, so a phrase split across runs with different formatting is still recognized, and the result is distributed back to the original runs. If the conversion changes the length of a paragraph, each run is converted on its own instead. Styles, comments, deleted text of tracked changes and field codes are kept.
+pub fn convert_docx<P: AsRef<Path>, Q: AsRef<Path>>(
+    opencc: &OpenCC,
+    input: P,
+    output: Q,
+) -> io::Result<()> {
+    convert_archive(input.as_ref(), output.as_ref(), |name, content| {
+        let part = name.strip_prefix("word/")?;
+
+        let is_text_part = matches!(part, "document.xml" | "footnotes.xml" | "endnotes.xml")
+            || ((part.starts_with("header") || part.starts_with("footer"))
+                && part.ends_with(".xml")
+                && !part.contains('/'));
+
+        if is_text_part {
+            Some(convert_xml(opencc, content, &DOCX))
+        } else {
+            None
+        }
+    })
+}
+
+/// Convert an OpenDocument Text (ODT) document and write the result to `output`, which may be `input` itself (the result replaces it when it is complete).
+///
+/// The text of paragraphs and headings in the content and in the headers and footers is converted paragraph by paragraph, so a phrase split across spans with different formatting is still recognized, and the result is distributed back to the original spans. If the conversion changes the length of a paragraph, each span is converted on its own instead. Styles, annotations (comments) and tracked changes are kept.
+pub fn convert_odt<P: AsRef<Path>, Q: AsRef<Path>>(
+    opencc: &OpenCC,
+    input: P,
+    output: Q,
+) -> io::Result<()> {
+    convert_archive(input.as_ref(), output.as_ref(), |name, content| match name {
+        "content.xml" | "styles.xml" => Some(convert_xml(opencc, content, &ODF)),
+        _ => None,
+    })
+}
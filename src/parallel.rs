@@ -0,0 +1,62 @@
+use rayon::prelude::*;
+
+use crate::{segments::SENTENCE_ENDS, OpenCC};
+
+/// The size (in bytes) of the pieces which `par_convert` splits a text into.
+const PIECE_SIZE: usize = 64 * 1024;
+
+/// Split a text into pieces of about `size` bytes, after a line break or else after a sentence-ending punctuation mark, so no phrase is split. A piece is longer if there is no such boundary.
+fn split_pieces(text: &str, size: usize) -> Vec<&str> {
+    let mut pieces = Vec::new();
+    let mut rest = text;
+
+    while rest.len() > size * 2 {
+        let mut at = size;
+
+        while !rest.is_char_boundary(at) {
+            at += 1;
+        }
+
+        let tail = &rest[at..];
+
+        let end = tail.find('\n').filter(|&i| i < size).map(|i| at + i + 1).or_else(|| {
+            tail.char_indices()
+                .find(|(_, c)| SENTENCE_ENDS.contains(c))
+                .map(|(i, c)| at + i + c.len_utf8())
+        });
+
+        match end {
+            Some(end) => {
+                pieces.push(&rest[..end]);
+
+                rest = &rest[end..];
+            },
+            None => break,
+        }
+    }
+
+    pieces.push(rest);
+
+    pieces
+}
+
+impl OpenCC {
+    /// Convert strings in parallel with the threads of rayon, and return the results in the same order.
+    pub fn convert_batch<S: AsRef<str> + Sync>(&self, inputs: &[S]) -> Vec<String> {
+        inputs.par_iter().map(|input| self.convert(input)).collect()
+    }
+
+    /// Convert a large string in parallel with the threads of rayon.
+    ///
+    /// The string is split after line breaks (or sentence-ending punctuation marks, for long paragraphs), the pieces are converted by the dictionaries in parallel and joined in order. The other stages, such as the punctuation stage, still see the whole string.
+    pub fn par_convert<S: AsRef<str>>(&self, input: S) -> String {
+        let input = self.pre_process(input.as_ref(), false);
+
+        let converted: Vec<String> = split_pieces(&input, PIECE_SIZE)
+            .into_par_iter()
+            .map(|piece| self.convert_with_dictionaries(piece))
+            .collect();
+
+        self.post_process(converted.concat(), false)
+    }
+}
@@ -0,0 +1,259 @@
+use std::ops::Range;
+
+use crate::{
+    segments::{apply_replacements, convert_segments, lines},
+    OpenCC,
+};
+
+/// Options for `convert_po`.
+#[derive(Debug, Clone, Default)]
+pub struct PoOptions {
+    /// Whether to convert translator comments (`# ...`), too. Extracted comments, references, flags and previous strings are never converted.
+    pub convert_translator_comments: bool,
+    /// Set the value of the `Language:` header, such as `zh_TW`.
+    pub language:                    Option<String>,
+    /// A flag (such as `fuzzy`) to add to the entries whose translations are changed by the conversion.
+    pub flag:                        Option<String>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Keyword {
+    Msgctxt,
+    Msgid,
+    Msgstr,
+    /// An obsolete entry (`#~`) or anything unknown, which is kept.
+    Other,
+}
+
+#[derive(Default)]
+struct Entry {
+    /// Where a new flags line should be inserted.
+    insert_at:   Option<usize>,
+    /// The content of the `#,` line, after `#,`.
+    flags:       Option<Range<usize>>,
+    has_msgctxt: bool,
+    /// The contents of the strings of `msgid`.
+    msgid:       Vec<Range<usize>>,
+    /// The contents of the strings of each `msgstr` or `msgstr[n]`.
+    msgstrs:     Vec<Vec<Range<usize>>>,
+}
+
+struct Converter<'a, 'b> {
+    opencc:       &'b OpenCC,
+    source:       &'a str,
+    options:      &'b PoOptions,
+    line_ending:  &'static str,
+    entry:        Entry,
+    keyword:      Keyword,
+    replacements: Vec<(Range<usize>, String)>,
+}
+
+/// The content of a quoted string which starts at `p` in the line ending at `end`, without the quotes.
+fn string_content(source: &str, p: usize, end: usize) -> Option<Range<usize>> {
+    let line = source[p..end].trim_end();
+
+    if line.len() >= 2 && line.starts_with('"') && line.ends_with('"') {
+        Some((p + 1)..(p + line.len() - 1))
+    } else {
+        None
+    }
+}
+
+impl<'a, 'b> Converter<'a, 'b> {
+    fn line(&mut self, range: Range<usize>) {
+        let Range {
+            start,
+            end,
+        } = range;
+        let line = &self.source[start..end];
+
+        if line.trim().is_empty() {
+            self.finish_entry();
+
+            return;
+        }
+
+        if let Some(rest) = line.strip_prefix('#') {
+            if self.keyword == Keyword::Msgstr {
+                self.finish_entry();
+            }
+
+            match rest.as_bytes().first() {
+                Some(b',') => self.entry.flags = Some((start + 2)..end),
+                Some(b'|') | Some(b'~') => {
+                    self.entry.insert_at.get_or_insert(start);
+                },
+                Some(b'.') | Some(b':') => (),
+                _ => {
+                    if self.options.convert_translator_comments {
+                        self.convert((start + 1)..end);
+                    }
+                },
+            }
+
+            if rest.starts_with('~') {
+                self.keyword = Keyword::Other;
+            }
+
+            return;
+        }
+
+        let (keyword, string_start) = if line.starts_with('"') {
+            (self.keyword, start)
+        } else {
+            let name_length = line.find([' ', '\t']).unwrap_or(line.len());
+            let name = &line[..name_length];
+
+            let keyword = match name {
+                "msgctxt" => Keyword::Msgctxt,
+                "msgid" => Keyword::Msgid,
+                "msgid_plural" => Keyword::Other,
+                _ if name.starts_with("msgstr") => Keyword::Msgstr,
+                _ => Keyword::Other,
+            };
+
+            if keyword != Keyword::Msgstr {
+                if self.keyword == Keyword::Msgstr {
+                    self.finish_entry();
+                }
+
+                self.entry.insert_at.get_or_insert(start);
+            }
+
+            match keyword {
+                Keyword::Msgctxt => self.entry.has_msgctxt = true,
+                Keyword::Msgstr => self.entry.msgstrs.push(Vec::new()),
+                _ => (),
+            }
+
+            self.keyword = keyword;
+
+            (keyword, start + line.len() - line[name_length..].trim_start().len())
+        };
+
+        if let Some(content) = string_content(self.source, string_start, end) {
+            match keyword {
+                Keyword::Msgid => self.entry.msgid.push(content),
+                Keyword::Msgstr => {
+                    if let Some(strings) = self.entry.msgstrs.last_mut() {
+                        strings.push(content);
+                    }
+                },
+                _ => (),
+            }
+        }
+    }
+
+    fn convert(&mut self, range: Range<usize>) {
+        let text = &self.source[range.clone()];
+        let converted = self.opencc.convert_in_document(text);
+
+        if converted != text {
+            self.replacements.push((range, converted));
+        }
+    }
+
+    fn finish_entry(&mut self) {
+        let entry = std::mem::take(&mut self.entry);
+
+        self.keyword = Keyword::Other;
+
+        let is_header = !entry.has_msgctxt
+            && !entry.msgid.is_empty()
+            && entry.msgid.iter().all(|range| range.is_empty());
+
+        if is_header {
+            if let Some(language) = self.options.language.as_ref() {
+                let mut found = false;
+
+                for range in entry.msgstrs.iter().flatten() {
+                    let header = &self.source[range.clone()];
+
+                    if let Some(value) = header.strip_prefix("Language:") {
+                        let value_start = range.start + header.len() - value.len();
+                        let value_length = value.find("\\n").unwrap_or(value.len());
+
+                        self.replacements.push((
+                            value_start..(value_start + value_length),
+                            format!(" {}", language),
+                        ));
+
+                        found = true;
+                    }
+                }
+
+                // append the field after the last string of the header, behind its closing quote
+                if let (false, Some(last)) = (found, entry.msgstrs.first().and_then(|s| s.last())) {
+                    let at = last.end + 1;
+
+                    self.replacements.push((
+                        at..at,
+                        format!("{}\"Language: {}\\n\"", self.line_ending, language),
+                    ));
+                }
+            }
+
+            return;
+        }
+
+        let mut changed = false;
+
+        for strings in entry.msgstrs.iter() {
+            let segments: Vec<&str> =
+                strings.iter().map(|range| &self.source[range.clone()]).collect();
+
+            let converted = convert_segments(self.opencc, &segments);
+
+            for (range, converted) in strings.iter().zip(converted) {
+                if self.source[range.clone()] != converted {
+                    self.replacements.push((range.clone(), converted));
+
+                    changed = true;
+                }
+            }
+        }
+
+        if let (true, Some(flag)) = (changed, self.options.flag.as_ref()) {
+            match entry.flags {
+                Some(flags) => {
+                    if !self.source[flags.clone()].split(',').any(|f| f.trim() == flag) {
+                        self.replacements.push((flags.end..flags.end, format!(", {}", flag)));
+                    }
+                },
+                None => {
+                    if let Some(insert_at) = entry.insert_at {
+                        self.replacements.push((
+                            insert_at..insert_at,
+                            format!("#, {}{}", flag, self.line_ending),
+                        ));
+                    }
+                },
+            }
+        }
+    }
+}
+
+/// Convert the translations of a Gettext PO (or POT) catalog, keeping its formatting.
+///
+/// Only `msgstr` and `msgstr[n]` are converted (strings which continue over several lines are converted as one unit), and optionally translator comments. `msgid`, `msgid_plural`, `msgctxt`, flags, references and obsolete entries are kept, and so is the header entry, except its `Language:` field if `options.language` is set (the field is added if the header has none).
+pub fn convert_po<S: AsRef<str>>(opencc: &OpenCC, po: S, options: &PoOptions) -> String {
+    let po = po.as_ref();
+
+    let mut converter = Converter {
+        opencc,
+        source: po,
+        options,
+        line_ending: if po.contains("\r\n") { "\r\n" } else { "\n" },
+        entry: Entry::default(),
+        keyword: Keyword::Other,
+        replacements: Vec::new(),
+    };
+
+    for line in lines(po) {
+        converter.line(line);
+    }
+
+    converter.finish_entry();
+
+    apply_replacements(po, converter.replacements)
+}
@@ -0,0 +1,140 @@
+/// The punctuation conventions of a region.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PunctuationStyle {
+    /// `“‘’”` quotation marks and the `·` interpunct.
+    Mainland,
+    /// `「『』」` quotation marks and the `‧` interpunct.
+    Taiwan,
+    /// `「『』」` quotation marks.
+    HongKong,
+}
+
+/// The mapping tables of the punctuation stage. See `OpenCC::set_punctuation`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Punctuation {
+    /// The outermost quotation marks, such as `('「', '」')`.
+    pub primary_quotes:   (char, char),
+    /// The quotation marks nested in the primary ones, such as `('『', '』')`. Deeper levels alternate between the two pairs.
+    pub secondary_quotes: (char, char),
+    /// Other characters to replace, as `(from, to)` pairs.
+    pub characters:       Vec<(char, char)>,
+}
+
+/// Where the punctuation stage is in a text which is mapped piece by piece.
+#[cfg(feature = "tokio")]
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct PunctuationState {
+    depth:    usize,
+    previous: Option<char>,
+}
+
+const OPENING_QUOTES: [char; 6] = ['“', '‘', '「', '『', '﹁', '﹃'];
+const CLOSING_QUOTES: [char; 6] = ['”', '’', '」', '』', '﹂', '﹄'];
+
+impl Punctuation {
+    /// Create the mapping tables for the conventions of a region.
+    pub fn new(style: PunctuationStyle) -> Punctuation {
+        match style {
+            PunctuationStyle::Mainland => Punctuation {
+                primary_quotes:   ('“', '”'),
+                secondary_quotes: ('‘', '’'),
+                characters:       vec![('‧', '·'), ('・', '·')],
+            },
+            PunctuationStyle::Taiwan => Punctuation {
+                primary_quotes:   ('「', '」'),
+                secondary_quotes: ('『', '』'),
+                characters:       vec![('·', '‧'), ('・', '‧')],
+            },
+            PunctuationStyle::HongKong => Punctuation {
+                primary_quotes:   ('「', '」'),
+                secondary_quotes: ('『', '』'),
+                characters:       Vec::new(),
+            },
+        }
+    }
+
+    /// Apply the mapping tables to a string.
+    ///
+    /// Quotation marks of any style are paired by their nesting, so `“外‘內’”` becomes `「外『內』」` and the other way around. Unpaired closing marks and apostrophes (like the `’` in `don’t`) are kept.
+    pub fn apply<S: AsRef<str>>(&self, text: S) -> String {
+        self.apply_inner(text.as_ref(), false, &mut 0, None, None)
+    }
+
+    /// Apply the mapping tables to the text of a document, without mapping ASCII characters or mapping to them, so that the syntax of the document is kept even with custom tables.
+    pub(crate) fn apply_in_document(&self, text: &str) -> String {
+        self.apply_inner(text, true, &mut 0, None, None)
+    }
+
+    /// Apply the mapping tables to a piece of a text, continuing from the state after the previous pieces, where `next` is the character after the piece.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn apply_piece(
+        &self,
+        text: &str,
+        state: &mut PunctuationState,
+        next: Option<char>,
+    ) -> String {
+        let output = self.apply_inner(text, false, &mut state.depth, state.previous, next);
+
+        if let Some(last) = text.chars().next_back() {
+            state.previous = Some(last);
+        }
+
+        output
+    }
+
+    fn apply_inner(
+        &self,
+        text: &str,
+        in_document: bool,
+        depth: &mut usize,
+        mut previous: Option<char>,
+        after: Option<char>,
+    ) -> String {
+        let mut output = String::with_capacity(text.len());
+
+        let mut chars = text.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            let is_apostrophe = (c == '’' || c == '‘')
+                && previous.map(|p: char| p.is_ascii_alphanumeric()).unwrap_or(false)
+                && chars
+                    .peek()
+                    .copied()
+                    .or(after)
+                    .map(|n| n.is_ascii_alphanumeric())
+                    .unwrap_or(false);
+
+            let quotes = |depth: usize| {
+                if depth % 2 == 0 {
+                    self.primary_quotes
+                } else {
+                    self.secondary_quotes
+                }
+            };
+
+            let mapped = if is_apostrophe {
+                c
+            } else if OPENING_QUOTES.contains(&c) {
+                *depth += 1;
+
+                quotes(*depth - 1).0
+            } else if CLOSING_QUOTES.contains(&c) && *depth > 0 {
+                *depth -= 1;
+
+                quotes(*depth).1
+            } else {
+                self.characters.iter().find(|(from, _)| *from == c).map(|(_, to)| *to).unwrap_or(c)
+            };
+
+            if in_document && (c.is_ascii() || mapped.is_ascii()) {
+                output.push(c);
+            } else {
+                output.push(mapped);
+            }
+
+            previous = Some(c);
+        }
+
+        output
+    }
+}
@@ -0,0 +1,614 @@
+use std::ops::Range;
+
+use crate::{
+    segments::{apply_replacements, lines},
+    OpenCC,
+};
+
+/// Options for `convert_yaml` and `convert_toml`.
+#[derive(Debug, Clone, Default)]
+pub struct ResourceOptions {
+    /// Rename a top-level key, such as `("zh-CN", "zh-TW")` for a Rails-style locale file.
+    pub locale_key: Option<(String, String)>,
+}
+
+struct Editor<'a, 'b> {
+    opencc:       &'b OpenCC,
+    source:       &'a str,
+    options:      &'b ResourceOptions,
+    replacements: Vec<(Range<usize>, String)>,
+}
+
+impl<'a, 'b> Editor<'a, 'b> {
+    fn new(opencc: &'b OpenCC, source: &'a str, options: &'b ResourceOptions) -> Self {
+        Editor {
+            opencc,
+            source,
+            options,
+            replacements: Vec::new(),
+        }
+    }
+
+    fn convert(&mut self, range: Range<usize>) {
+        let text = &self.source[range.clone()];
+
+        if text.is_empty() {
+            return;
+        }
+
+        let converted = self.opencc.convert_in_document(text);
+
+        if converted != text {
+            self.replacements.push((range, converted));
+        }
+    }
+
+    fn rename_key(&mut self, range: Range<usize>) {
+        if let Some((from, to)) = self.options.locale_key.as_ref() {
+            if self.source[range.clone()] == *from {
+                self.replacements.push((range, to.clone()));
+            }
+        }
+    }
+
+    fn finish(self) -> String {
+        apply_replacements(self.source, self.replacements)
+    }
+}
+
+#[inline]
+fn is_blank(b: u8) -> bool {
+    b == b' ' || b == b'\t'
+}
+
+#[derive(Clone, Copy)]
+enum YamlState {
+    Normal,
+    /// Inside a block scalar (`|` or `>`) which belongs to a line with this indentation.
+    BlockScalar {
+        indent: usize,
+    },
+    /// Inside a quoted scalar which spans lines.
+    Quoted {
+        quote: u8,
+    },
+    /// Inside a flow collection which spans lines.
+    Flow {
+        depth: usize,
+    },
+}
+
+/// Find the closing quote of a YAML quoted scalar whose content starts at `start`.
+fn find_yaml_closing_quote(bytes: &[u8], start: usize, end: usize, quote: u8) -> Option<usize> {
+    let mut p = start;
+
+    while p < end {
+        match bytes[p] {
+            b'\\' if quote == b'"' => p += 2,
+            b'\'' if quote == b'\'' && bytes.get(p + 1) == Some(&b'\'') && p + 1 < end => p += 2,
+            b if b == quote => return Some(p),
+            _ => p += 1,
+        }
+    }
+
+    None
+}
+
+struct YamlConverter<'a, 'b> {
+    editor: Editor<'a, 'b>,
+    bytes:  &'a [u8],
+    state:  YamlState,
+}
+
+impl<'a, 'b> YamlConverter<'a, 'b> {
+    #[inline]
+    fn is_comment_start(&self, p: usize, line_start: usize) -> bool {
+        self.bytes[p] == b'#' && (p == line_start || is_blank(self.bytes[p - 1]))
+    }
+
+    fn line(&mut self, range: Range<usize>) {
+        let Range {
+            start,
+            end,
+        } = range;
+        let bytes = self.bytes;
+
+        match self.state {
+            YamlState::Normal => (),
+            YamlState::BlockScalar {
+                indent,
+            } => {
+                let line = &self.editor.source[start..end];
+
+                if line.trim().is_empty() || line.len() - line.trim_start().len() > indent {
+                    self.editor.convert(start..end);
+
+                    return;
+                }
+
+                self.state = YamlState::Normal;
+            },
+            YamlState::Quoted {
+                quote,
+            } => {
+                match find_yaml_closing_quote(bytes, start, end, quote) {
+                    Some(close) => {
+                        self.editor.convert(start..close);
+
+                        self.state = YamlState::Normal;
+                    },
+                    None => self.editor.convert(start..end),
+                }
+
+                return;
+            },
+            YamlState::Flow {
+                depth,
+            } => {
+                self.flow(start, start, end, depth);
+
+                return;
+            },
+        }
+
+        let line = &self.editor.source[start..end];
+        let indent = line.len() - line.trim_start_matches(' ').len();
+
+        let mut p = start + indent;
+
+        let rest = &self.editor.source[p..end];
+
+        if rest.is_empty() || rest.starts_with('#') || rest.starts_with('%') {
+            return;
+        }
+
+        if indent == 0
+            && (rest.starts_with("---") || rest.starts_with("..."))
+            && rest.as_bytes().get(3).map(|&b| is_blank(b)).unwrap_or(true)
+        {
+            self.value(start, start + 3, end, indent);
+
+            return;
+        }
+
+        let mut top_level = indent == 0;
+
+        // sequence entries
+        while bytes[p] == b'-' && (p + 1 == end || is_blank(bytes[p + 1])) {
+            top_level = false;
+
+            p += 1;
+
+            while p < end && is_blank(bytes[p]) {
+                p += 1;
+            }
+
+            if p == end {
+                return;
+            }
+        }
+
+        match bytes[p] {
+            // a complex key
+            b'?' if p + 1 == end || is_blank(bytes[p + 1]) => return,
+            b':' if p + 1 == end || is_blank(bytes[p + 1]) => {
+                self.value(start, p + 1, end, indent);
+
+                return;
+            },
+            _ => (),
+        }
+
+        match self.find_key(start, p, end) {
+            Some((key, colon)) => {
+                if top_level {
+                    self.editor.rename_key(key);
+                }
+
+                self.value(start, colon + 1, end, indent);
+            },
+            None => self.value(start, p, end, indent),
+        }
+    }
+
+    /// Find a mapping key which starts at `p`. Returns the range of the key (without quotes) and the position of the colon.
+    fn find_key(&self, line_start: usize, p: usize, end: usize) -> Option<(Range<usize>, usize)> {
+        let bytes = self.bytes;
+
+        let is_indicator = |c: usize| bytes[c] == b':' && (c + 1 == end || is_blank(bytes[c + 1]));
+
+        match bytes[p] {
+            quote @ (b'"' | b'\'') => {
+                let close = find_yaml_closing_quote(bytes, p + 1, end, quote)?;
+
+                let mut c = close + 1;
+
+                while c < end && is_blank(bytes[c]) {
+                    c += 1;
+                }
+
+                if c < end && is_indicator(c) {
+                    Some(((p + 1)..close, c))
+                } else {
+                    None
+                }
+            },
+            b'[' | b'{' | b'&' | b'*' | b'!' | b'|' | b'>' | b'#' => None,
+            _ => {
+                for c in p..end {
+                    if self.is_comment_start(c, line_start) {
+                        return None;
+                    }
+
+                    if is_indicator(c) {
+                        let key = self.editor.source[p..c].trim_end();
+
+                        return Some((p..(p + key.len()), c));
+                    }
+                }
+
+                None
+            },
+        }
+    }
+
+    /// Convert a value which starts at `p`.
+    fn value(&mut self, line_start: usize, mut p: usize, end: usize, indent: usize) {
+        let bytes = self.bytes;
+
+        loop {
+            while p < end && is_blank(bytes[p]) {
+                p += 1;
+            }
+
+            if p >= end {
+                return;
+            }
+
+            match bytes[p] {
+                // anchors, aliases and tags
+                b'&' | b'*' | b'!' => {
+                    while p < end && !is_blank(bytes[p]) {
+                        p += 1;
+                    }
+                },
+                b'#' => return,
+                b'|' | b'>' => {
+                    self.state = YamlState::BlockScalar {
+                        indent,
+                    };
+
+                    return;
+                },
+                quote @ (b'"' | b'\'') => {
+                    match find_yaml_closing_quote(bytes, p + 1, end, quote) {
+                        Some(close) => self.editor.convert((p + 1)..close),
+                        None => {
+                            self.editor.convert((p + 1)..end);
+
+                            self.state = YamlState::Quoted {
+                                quote,
+                            };
+                        },
+                    }
+
+                    return;
+                },
+                b'[' | b'{' => {
+                    self.flow(line_start, p, end, 0);
+
+                    return;
+                },
+                _ => {
+                    let mut e = p;
+
+                    while e < end && !self.is_comment_start(e, line_start) {
+                        e += 1;
+                    }
+
+                    self.editor.convert(p..e);
+
+                    return;
+                },
+            }
+        }
+    }
+
+    /// Convert the values in a flow collection, from `p` to the end of the line.
+    fn flow(&mut self, line_start: usize, mut p: usize, end: usize, mut depth: usize) {
+        let bytes = self.bytes;
+
+        let is_flow_indicator = |b: u8| matches!(b, b',' | b'[' | b']' | b'{' | b'}');
+
+        while p < end {
+            match bytes[p] {
+                b'[' | b'{' => {
+                    depth += 1;
+                    p += 1;
+                },
+                b']' | b'}' => {
+                    depth = depth.saturating_sub(1);
+                    p += 1;
+
+                    if depth == 0 {
+                        break;
+                    }
+                },
+                b',' | b':' | b' ' | b'\t' => p += 1,
+                b'#' if self.is_comment_start(p, line_start) => break,
+                b'&' | b'*' | b'!' => {
+                    while p < end && !is_blank(bytes[p]) && !is_flow_indicator(bytes[p]) {
+                        p += 1;
+                    }
+                },
+                quote @ (b'"' | b'\'') => match find_yaml_closing_quote(bytes, p + 1, end, quote) {
+                    Some(close) => {
+                        let mut c = close + 1;
+
+                        while c < end && is_blank(bytes[c]) {
+                            c += 1;
+                        }
+
+                        if c >= end || bytes[c] != b':' {
+                            self.editor.convert((p + 1)..close);
+                        }
+
+                        p = close + 1;
+                    },
+                    None => {
+                        self.editor.convert((p + 1)..end);
+
+                        p = end;
+                    },
+                },
+                _ => {
+                    let mut e = p;
+
+                    while e < end
+                        && !is_flow_indicator(bytes[e])
+                        && !(bytes[e] == b':'
+                            && (e + 1 == end
+                                || is_blank(bytes[e + 1])
+                                || is_flow_indicator(bytes[e + 1])))
+                        && !self.is_comment_start(e, line_start)
+                    {
+                        e += 1;
+                    }
+
+                    if e >= end || bytes[e] != b':' {
+                        self.editor.convert(p..e);
+                    }
+
+                    p = e;
+                },
+            }
+        }
+
+        self.state = if depth > 0 {
+            YamlState::Flow {
+                depth,
+            }
+        } else {
+            YamlState::Normal
+        };
+    }
+}
+
+/// Convert the string values in a YAML document, keeping keys, anchors, aliases, tags, comments and the formatting.
+///
+/// Plain, quoted and block scalars are converted, including those in flow collections. If `options.locale_key` is set, a matching top-level key is renamed, so `zh-CN:` becomes `zh-TW:`.
+pub fn convert_yaml<S: AsRef<str>>(opencc: &OpenCC, yaml: S, options: &ResourceOptions) -> String {
+    let yaml = yaml.as_ref();
+
+    let mut converter = YamlConverter {
+        editor: Editor::new(opencc, yaml, options),
+        bytes:  yaml.as_bytes(),
+        state:  YamlState::Normal,
+    };
+
+    for line in lines(yaml) {
+        converter.line(line);
+    }
+
+    converter.editor.finish()
+}
+
+enum TomlContainer {
+    Array,
+    InlineTable { expecting_key: bool },
+}
+
+/// Find the end (exclusive) of a TOML string which starts at `p`, and the range of its content.
+fn find_toml_string(bytes: &[u8], p: usize) -> (Range<usize>, usize) {
+    let quote = bytes[p];
+    let is_basic = quote == b'"';
+
+    let is_multi_line = bytes.len() >= p + 3 && bytes[p + 1] == quote && bytes[p + 2] == quote;
+
+    let content_start = if is_multi_line { p + 3 } else { p + 1 };
+
+    let mut e = content_start;
+
+    while e < bytes.len() {
+        match bytes[e] {
+            b'\\' if is_basic => e += 2,
+            b'\n' if !is_multi_line => break,
+            b if b == quote => {
+                if !is_multi_line {
+                    return (content_start..e, e + 1);
+                }
+
+                if bytes.len() >= e + 3 && bytes[e + 1] == quote && bytes[e + 2] == quote {
+                    // up to two more quotes can be a part of the content
+                    let mut close = e;
+
+                    while close < e + 2 && bytes.get(close + 3) == Some(&quote) {
+                        close += 1;
+                    }
+
+                    return (content_start..close, close + 3);
+                }
+
+                e += 1;
+            },
+            _ => e += 1,
+        }
+    }
+
+    let e = e.min(bytes.len());
+
+    (content_start..e, e)
+}
+
+/// Parse a (dotted) TOML key which starts at `p`. Returns the range of its first part (without quotes) and the position after the key.
+fn parse_toml_key(bytes: &[u8], mut p: usize) -> (Option<Range<usize>>, usize) {
+    let mut first = None;
+
+    loop {
+        while p < bytes.len() && is_blank(bytes[p]) {
+            p += 1;
+        }
+
+        let part = match bytes.get(p) {
+            Some(b'"') | Some(b'\'') => {
+                let (content, next) = find_toml_string(bytes, p);
+
+                p = next;
+
+                content
+            },
+            _ => {
+                let start = p;
+
+                while p < bytes.len()
+                    && (bytes[p].is_ascii_alphanumeric() || bytes[p] == b'-' || bytes[p] == b'_')
+                {
+                    p += 1;
+                }
+
+                start..p
+            },
+        };
+
+        if first.is_none() {
+            first = Some(part);
+        }
+
+        while p < bytes.len() && is_blank(bytes[p]) {
+            p += 1;
+        }
+
+        if bytes.get(p) == Some(&b'.') {
+            p += 1;
+        } else {
+            return (first, p);
+        }
+    }
+}
+
+/// Convert the string values in a TOML document, keeping keys, comments and the formatting.
+///
+/// If `options.locale_key` is set, a matching key at the top level (as the first part of a table header like `[zh-CN]`, or as a key of the root table) is renamed.
+pub fn convert_toml<S: AsRef<str>>(opencc: &OpenCC, toml: S, options: &ResourceOptions) -> String {
+    let toml = toml.as_ref();
+    let bytes = toml.as_bytes();
+
+    let mut editor = Editor::new(opencc, toml, options);
+    let mut stack: Vec<TomlContainer> = Vec::new();
+
+    let line_end = |p: usize| toml[p..].find('\n').map(|i| p + i).unwrap_or(toml.len());
+
+    let mut p = 0;
+    let mut at_statement = true;
+    let mut in_root = true;
+
+    while p < bytes.len() {
+        if at_statement && stack.is_empty() {
+            match bytes[p] {
+                b' ' | b'\t' | b'\r' | b'\n' => p += 1,
+                b'#' => p = line_end(p),
+                b'[' => {
+                    let key_start = if bytes.get(p + 1) == Some(&b'[') { p + 2 } else { p + 1 };
+
+                    if let (Some(first), _) = parse_toml_key(bytes, key_start) {
+                        editor.rename_key(first);
+                    }
+
+                    in_root = false;
+
+                    p = line_end(p);
+                },
+                _ => {
+                    let (first, next) = parse_toml_key(bytes, p);
+
+                    if let (true, Some(first)) = (in_root, first) {
+                        editor.rename_key(first);
+                    }
+
+                    if bytes.get(next) == Some(&b'=') {
+                        p = next + 1;
+
+                        at_statement = false;
+                    } else {
+                        p = line_end(p);
+                    }
+                },
+            }
+
+            continue;
+        }
+
+        match bytes[p] {
+            b'\n' => {
+                if stack.is_empty() {
+                    at_statement = true;
+                }
+
+                p += 1;
+            },
+            b'#' => p = line_end(p),
+            b'"' | b'\'' => {
+                let (content, next) = find_toml_string(bytes, p);
+
+                match stack.last() {
+                    Some(TomlContainer::InlineTable {
+                        expecting_key: true,
+                    }) => (),
+                    _ => editor.convert(content),
+                }
+
+                p = next;
+            },
+            b'[' => {
+                stack.push(TomlContainer::Array);
+
+                p += 1;
+            },
+            b'{' => {
+                stack.push(TomlContainer::InlineTable {
+                    expecting_key: true
+                });
+
+                p += 1;
+            },
+            b']' | b'}' => {
+                stack.pop();
+
+                p += 1;
+            },
+            b',' | b'=' => {
+                if let Some(TomlContainer::InlineTable {
+                    expecting_key,
+                }) = stack.last_mut()
+                {
+                    *expecting_key = bytes[p] == b',';
+                }
+
+                p += 1;
+            },
+            _ => p += 1,
+        }
+    }
+
+    editor.finish()
+}
@@ -0,0 +1,160 @@
+use std::ops::Range;
+
+use crate::{changes::map_range, find_changes, DefaultConfig, OpenCC};
+
+/// Why a span of text does not return to the original after a round trip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RoundTripLossKind {
+    /// Several characters are converted to the same one, such as `乾`, `幹` and `干` to `干`, and the inverse picks another of them.
+    CharacterMerge,
+    /// A phrase is converted as a whole, such as `鼠标` to `滑鼠`, and the inverse phrase is not the original one.
+    PhraseIdiom,
+    /// A variant form is converted to a form whose inverse is another variant, such as `着` and `著`.
+    Variant,
+}
+
+/// A span of text which does not return to the original after a round trip.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundTripLoss {
+    /// The byte range of the original text.
+    pub source_range: Range<usize>,
+    pub original:     String,
+    /// The forward conversion of the span, with the whole phrase it belongs to.
+    pub converted:    String,
+    pub restored:     String,
+    pub kind:         RoundTripLossKind,
+}
+
+/// The result of `RoundTripChecker::check` and `round_trip_check`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoundTrip {
+    /// The forward conversion of the text.
+    pub converted: String,
+    /// The inverse conversion of `converted`.
+    pub restored:  String,
+    pub losses:    Vec<RoundTripLoss>,
+}
+
+impl RoundTrip {
+    /// Whether the text returns to the original.
+    #[inline]
+    pub fn is_lossless(&self) -> bool {
+        self.losses.is_empty()
+    }
+}
+
+fn classify(
+    forward: &OpenCC,
+    original: &str,
+    converted: &str,
+    restored: &str,
+) -> RoundTripLossKind {
+    let by_character: String =
+        original.chars().map(|c| forward.convert(c.encode_utf8(&mut [0; 4]))).collect();
+
+    if by_character != converted {
+        RoundTripLossKind::PhraseIdiom
+    } else if forward.convert(restored) == forward.convert(original) {
+        RoundTripLossKind::CharacterMerge
+    } else {
+        RoundTripLossKind::Variant
+    }
+}
+
+/// Converts texts forward and back with two `OpenCC` instances, which are loaded once, and reports every span which does not return to the original.
+///
+/// ```rust,ignore
+/// let checker = RoundTripChecker::new(DefaultConfig::S2T).unwrap();
+///
+/// for text in ["头发和干燥", "鼠标"] {
+///     for loss in checker.check(text).losses.iter() {
+///         println!("{} -> {} -> {} ({:?})", loss.original, loss.converted, loss.restored, loss.kind);
+///     }
+/// }
+/// ```
+pub struct RoundTripChecker {
+    forward: OpenCC,
+    inverse: OpenCC,
+}
+
+impl RoundTripChecker {
+    /// Load a default config and its inverse (see `DefaultConfig::inverse`).
+    pub fn new(forward: DefaultConfig) -> Result<RoundTripChecker, &'static str> {
+        Ok(RoundTripChecker {
+            forward: OpenCC::new(forward)?,
+            inverse: OpenCC::new(forward.inverse())?,
+        })
+    }
+
+    /// Use two loaded `OpenCC` instances, where `inverse` converts the output of `forward` back.
+    #[inline]
+    pub fn from_opencc(forward: OpenCC, inverse: OpenCC) -> RoundTripChecker {
+        RoundTripChecker {
+            forward,
+            inverse,
+        }
+    }
+
+    /// Get the `OpenCC` instance which converts forward.
+    #[inline]
+    pub fn get_forward(&self) -> &OpenCC {
+        &self.forward
+    }
+
+    /// Get the `OpenCC` instance which converts back.
+    #[inline]
+    pub fn get_inverse(&self) -> &OpenCC {
+        &self.inverse
+    }
+
+    /// Convert a text forward and back, and report every span which does not return to the original.
+    pub fn check<S: AsRef<str>>(&self, text: S) -> RoundTrip {
+        let text = text.as_ref();
+
+        let converted = self.forward.convert(text);
+        let restored = self.inverse.convert(&converted);
+
+        let forward_changes = find_changes(text, &converted);
+
+        let losses = find_changes(text, &restored)
+            .into_iter()
+            .map(|change| {
+                let converted_range = map_range(&forward_changes, change.source_range.clone());
+                let converted_span = &converted[converted_range];
+
+                RoundTripLoss {
+                    kind:         classify(&self.forward, &change.from, converted_span, &change.to),
+                    source_range: change.source_range,
+                    original:     change.from,
+                    converted:    converted_span.to_string(),
+                    restored:     change.to,
+                }
+            })
+            .collect();
+
+        RoundTrip {
+            converted,
+            restored,
+            losses,
+        }
+    }
+}
+
+/// Convert a text with a default config and back with its inverse (see `DefaultConfig::inverse`), and report every span which does not return to the original.
+///
+/// Both configs are loaded on every call. Use a `RoundTripChecker` to check several texts.
+///
+/// ```rust,ignore
+/// let round_trip = round_trip_check("头发和干燥", DefaultConfig::S2T).unwrap();
+///
+/// for loss in round_trip.losses.iter() {
+///     println!("{} -> {} -> {} ({:?})", loss.original, loss.converted, loss.restored, loss.kind);
+/// }
+/// ```
+#[inline]
+pub fn round_trip_check<S: AsRef<str>>(
+    text: S,
+    forward: DefaultConfig,
+) -> Result<RoundTrip, &'static str> {
+    Ok(RoundTripChecker::new(forward)?.check(text))
+}
@@ -0,0 +1,88 @@
+use std::ops::Range;
+
+use crate::OpenCC;
+
+/// Characters after which a text can be split without splitting a phrase, besides line breaks.
+#[cfg(any(feature = "rayon", feature = "tokio"))]
+pub(crate) const SENTENCE_ENDS: [char; 6] = ['。', '！', '？', '；', '!', '?'];
+
+/// Find the end of the last line break or sentence-ending punctuation mark of a text.
+#[cfg(feature = "tokio")]
+pub(crate) fn last_boundary(text: &str) -> Option<usize> {
+    text.char_indices()
+        .rev()
+        .find(|&(_, c)| c == '\n' || SENTENCE_ENDS.contains(&c))
+        .map(|(i, c)| i + c.len_utf8())
+}
+
+/// Convert pieces of text which belong together (e.g. text split by inline markup), so that a phrase spanning several pieces is still recognized, and distribute the result back to the pieces.
+///
+/// If the conversion keeps the number of characters, the converted text is split at the original character offsets. Otherwise, each piece is converted on its own.
+pub(crate) fn convert_segments<S: AsRef<str>>(opencc: &OpenCC, segments: &[S]) -> Vec<String> {
+    if segments.len() < 2 {
+        return segments
+            .iter()
+            .map(|segment| opencc.convert_in_document(segment.as_ref()))
+            .collect();
+    }
+
+    let joined: String = segments.iter().map(|segment| segment.as_ref()).collect();
+    let converted = opencc.convert_in_document(&joined);
+
+    if converted.chars().count() != joined.chars().count() {
+        return segments
+            .iter()
+            .map(|segment| opencc.convert_in_document(segment.as_ref()))
+            .collect();
+    }
+
+    let mut chars = converted.chars();
+
+    segments
+        .iter()
+        .map(|segment| chars.by_ref().take(segment.as_ref().chars().count()).collect())
+        .collect()
+}
+
+/// Replace byte ranges of `source`. The ranges must not overlap.
+pub(crate) fn apply_replacements(
+    source: &str,
+    mut replacements: Vec<(Range<usize>, String)>,
+) -> String {
+    replacements.sort_by_key(|(range, _)| range.start);
+
+    let mut output = String::with_capacity(source.len());
+    let mut offset = 0;
+
+    for (range, replacement) in replacements {
+        output.push_str(&source[offset..range.start]);
+        output.push_str(&replacement);
+
+        offset = range.end;
+    }
+
+    output.push_str(&source[offset..]);
+
+    output
+}
+
+/// Iterate over the lines of `text` as byte ranges without the line endings.
+pub(crate) fn lines(text: &str) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut start = 0;
+
+    text.split_inclusive('\n').map(move |line| {
+        let line_start = start;
+
+        start += line.len();
+
+        let ending_length = if line.ends_with("\r\n") {
+            2
+        } else if line.ends_with('\n') {
+            1
+        } else {
+            0
+        };
+
+        line_start..(start - ending_length)
+    })
+}
@@ -0,0 +1,122 @@
+//! Modules for the `with`, `serialize_with` and `deserialize_with` attributes of serde, which convert string fields with the shared instance (see `OpenCC::shared`) of a default config.
+//!
+//! ```rust,ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Product {
+//!     #[serde(serialize_with = "opencc_rust::serde::s2twp::serialize")]
+//!     name:        String,
+//!     #[serde(with = "opencc_rust::serde::s2twp::option")]
+//!     description: Option<String>,
+//! }
+//! ```
+
+use ::serde::{de, ser, Deserialize, Deserializer, Serializer};
+
+use crate::{DefaultConfig, OpenCC};
+
+#[inline]
+fn convert(config: DefaultConfig, value: &str) -> Result<String, &'static str> {
+    Ok(OpenCC::shared(config)?.convert(value))
+}
+
+fn serialize<S: Serializer>(
+    config: DefaultConfig,
+    value: &str,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&convert(config, value).map_err(ser::Error::custom)?)
+}
+
+fn deserialize<'de, D: Deserializer<'de>>(
+    config: DefaultConfig,
+    deserializer: D,
+) -> Result<String, D::Error> {
+    let value = String::deserialize(deserializer)?;
+
+    convert(config, &value).map_err(de::Error::custom)
+}
+
+fn serialize_option<S: Serializer>(
+    config: DefaultConfig,
+    value: Option<&str>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    match value {
+        Some(value) => {
+            serializer.serialize_some(&convert(config, value).map_err(ser::Error::custom)?)
+        },
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_option<'de, D: Deserializer<'de>>(
+    config: DefaultConfig,
+    deserializer: D,
+) -> Result<Option<String>, D::Error> {
+    match Option::<String>::deserialize(deserializer)? {
+        Some(value) => convert(config, &value).map(Some).map_err(de::Error::custom),
+        None => Ok(None),
+    }
+}
+
+macro_rules! config_module {
+    ($module:ident, $config:ident) => {
+        #[doc = concat!("Convert a string field with `DefaultConfig::", stringify!($config), "`. The `option` module is for an `Option<String>` field.")]
+        pub mod $module {
+            use ::serde::{Deserializer, Serializer};
+
+            use crate::DefaultConfig;
+
+            pub fn serialize<T: AsRef<str> + ?Sized, S: Serializer>(
+                value: &T,
+                serializer: S,
+            ) -> Result<S::Ok, S::Error> {
+                super::serialize(DefaultConfig::$config, value.as_ref(), serializer)
+            }
+
+            pub fn deserialize<'de, D: Deserializer<'de>>(
+                deserializer: D,
+            ) -> Result<String, D::Error> {
+                super::deserialize(DefaultConfig::$config, deserializer)
+            }
+
+            pub mod option {
+                use ::serde::{Deserializer, Serializer};
+
+                use crate::DefaultConfig;
+
+                pub fn serialize<T: AsRef<str>, S: Serializer>(
+                    value: &Option<T>,
+                    serializer: S,
+                ) -> Result<S::Ok, S::Error> {
+                    super::super::serialize_option(
+                        DefaultConfig::$config,
+                        value.as_ref().map(|value| value.as_ref()),
+                        serializer,
+                    )
+                }
+
+                pub fn deserialize<'de, D: Deserializer<'de>>(
+                    deserializer: D,
+                ) -> Result<Option<String>, D::Error> {
+                    super::super::deserialize_option(DefaultConfig::$config, deserializer)
+                }
+            }
+        }
+    };
+}
+
+config_module!(hk2s, HK2S);
+config_module!(hk2t, HK2T);
+config_module!(jp2t, JP2T);
+config_module!(s2hk, S2HK);
+config_module!(s2t, S2T);
+config_module!(s2tw, S2TW);
+config_module!(s2twp, S2TWP);
+config_module!(t2hk, T2HK);
+config_module!(t2jp, T2JP);
+config_module!(t2s, T2S);
+config_module!(t2tw, T2TW);
+config_module!(tw2s, TW2S);
+config_module!(tw2sp, TW2SP);
+config_module!(tw2t, TW2T);
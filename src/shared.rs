@@ -0,0 +1,135 @@
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    ptr,
+    sync::{
+        atomic::{AtomicPtr, Ordering},
+        Arc, Mutex, MutexGuard, PoisonError, RwLock,
+    },
+};
+
+#[cfg(feature = "static-dictionaries")]
+use crate::generate_static_dictionary;
+use crate::{DefaultConfig, OpenCC};
+
+/// The shared instance of a default config.
+#[derive(Default)]
+struct Slot {
+    instance: RwLock<Option<Arc<OpenCC>>>,
+    /// Held while the config is opened, so that other threads wait for it instead of opening their own copies. Other configs are not blocked.
+    opening:  Mutex<()>,
+}
+
+impl Slot {
+    #[inline]
+    fn get(&self) -> Option<Arc<OpenCC>> {
+        self.instance.read().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+}
+
+#[derive(Default)]
+struct Registry {
+    slots:     RwLock<HashMap<DefaultConfig, Arc<Slot>>>,
+    directory: Mutex<Option<PathBuf>>,
+}
+
+static REGISTRY: AtomicPtr<Registry> = AtomicPtr::new(ptr::null_mut());
+
+/// Get the registry, which is created on first use and lives as long as the process.
+fn registry() -> &'static Registry {
+    let registry = REGISTRY.load(Ordering::Acquire);
+
+    if !registry.is_null() {
+        // it is never freed after it is published
+        return unsafe { &*registry };
+    }
+
+    let new = Box::into_raw(Box::default());
+
+    match REGISTRY.compare_exchange(ptr::null_mut(), new, Ordering::AcqRel, Ordering::Acquire) {
+        Ok(_) => unsafe { &*new },
+        Err(registry) => {
+            // another thread published its registry first
+            drop(unsafe { Box::from_raw(new) });
+
+            unsafe { &*registry }
+        },
+    }
+}
+
+// the locks protect no invariants, so they are used even if a thread panicked while holding them
+
+#[inline]
+fn shared_directory() -> MutexGuard<'static, Option<PathBuf>> {
+    registry().directory.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+fn slot(config: DefaultConfig) -> Arc<Slot> {
+    let slots = &registry().slots;
+
+    if let Some(slot) = slots.read().unwrap_or_else(PoisonError::into_inner).get(&config) {
+        return slot.clone();
+    }
+
+    slots.write().unwrap_or_else(PoisonError::into_inner).entry(config).or_default().clone()
+}
+
+impl OpenCC {
+    /// Get the process-wide instance of a default config. It is opened on first use, and the same instance (and the same copy of the dictionaries) is returned afterwards.
+    ///
+    /// A config which cannot be opened is not remembered, so it is retried on the next call. Once a config is opened, getting it only takes read locks, and opening a config does not block the calls for other configs.
+    ///
+    /// ```rust,ignore
+    /// let opencc = OpenCC::shared(DefaultConfig::S2TWP).unwrap();
+    ///
+    /// assert_eq!("涼風有訊", &opencc.convert("凉风有讯"));
+    /// ```
+    pub fn shared(config: DefaultConfig) -> Result<Arc<OpenCC>, &'static str> {
+        let slot = slot(config);
+
+        if let Some(opencc) = slot.get() {
+            return Ok(opencc);
+        }
+
+        let _opening = slot.opening.lock().unwrap_or_else(PoisonError::into_inner);
+
+        // another thread may have opened it while this one was waiting
+        if let Some(opencc) = slot.get() {
+            return Ok(opencc);
+        }
+
+        let directory = shared_directory().clone();
+
+        let opencc = match directory {
+            Some(directory) => {
+                #[cfg(feature = "static-dictionaries")]
+                generate_static_dictionary(&directory, config)?;
+
+                OpenCC::new(directory.join(config.get_file_name()))?
+            },
+            None => OpenCC::new(config)?,
+        };
+
+        let opencc = Arc::new(opencc);
+
+        *slot.instance.write().unwrap_or_else(PoisonError::into_inner) = Some(opencc.clone());
+
+        Ok(opencc)
+    }
+
+    /// Open the shared instances of default configs ahead of time, such as when a service starts. The first config which cannot be opened is returned with the error.
+    pub fn preload_shared(configs: &[DefaultConfig]) -> Result<(), (DefaultConfig, &'static str)> {
+        for &config in configs {
+            OpenCC::shared(config).map_err(|error| (config, error))?;
+        }
+
+        Ok(())
+    }
+
+    /// Set the directory which the shared instances load their config files and dictionaries from, or `None` (the default) for the data directory of OpenCC. With the `static-dictionaries` feature, the embedded dictionaries are written to the directory when a config is opened.
+    ///
+    /// The shared instances which are already opened are kept.
+    pub fn set_shared_dictionary_directory(directory: Option<PathBuf>) {
+        *shared_directory() = directory;
+    }
+}
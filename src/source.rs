@@ -0,0 +1,518 @@
+use std::{ops::Range, path::Path, str::FromStr};
+
+use crate::{segments::apply_replacements, OpenCC};
+
+/// A programming language for `convert_source`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    Rust,
+    /// C and C++.
+    C,
+    Java,
+    /// JavaScript and TypeScript.
+    JavaScript,
+    Python,
+    Go,
+}
+
+impl Language {
+    /// Guess the language of a source file by its extension.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Option<Language> {
+        path.as_ref().extension()?.to_str()?.parse().ok()
+    }
+}
+
+impl FromStr for Language {
+    type Err = &'static str;
+
+    /// Parse a language name or a file extension, such as `rust`, `rs` or `tsx`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.to_ascii_lowercase().as_str() {
+            "rust" | "rs" => Language::Rust,
+            "c" | "h" | "cpp" | "c++" | "cc" | "cxx" | "hpp" | "hh" | "hxx" => Language::C,
+            "java" => Language::Java,
+            "javascript" | "js" | "jsx" | "mjs" | "cjs" | "typescript" | "ts" | "tsx" | "mts"
+            | "cts" => Language::JavaScript,
+            "python" | "py" | "pyi" => Language::Python,
+            "go" => Language::Go,
+            _ => return Err("Unknown programming language."),
+        })
+    }
+}
+
+/// Options for `convert_source`.
+#[derive(Debug, Clone, Default)]
+pub struct SourceOptions {
+    /// Only convert comments, and keep string literals.
+    pub comments_only: bool,
+}
+
+/// A string literal with embedded expressions, such as a JavaScript template literal or a Python f-string.
+#[derive(Clone, Copy)]
+struct Interpolation {
+    closing: &'static [u8],
+    opening: &'static [u8],
+    escapes: bool,
+}
+
+const TEMPLATE_LITERAL: Interpolation =
+    Interpolation {
+        closing: b"`", opening: b"${", escapes: true
+    };
+
+/// The keywords of JavaScript after which a `/` starts a regular expression literal instead of a division.
+const REGEX_KEYWORDS: [&[u8]; 13] = [
+    b"return",
+    b"typeof",
+    b"instanceof",
+    b"in",
+    b"of",
+    b"new",
+    b"delete",
+    b"void",
+    b"throw",
+    b"case",
+    b"do",
+    b"else",
+    b"yield",
+];
+
+struct Scanner<'a> {
+    bytes:    &'a [u8],
+    language: Language,
+    p:        usize,
+    comments: Vec<Range<usize>>,
+    strings:  Vec<Range<usize>>,
+    /// The brace depths of the code in the `${...}` of JavaScript template literals (or the `{...}` of Python f-strings), and the strings to return to.
+    template: Vec<(usize, Interpolation)>,
+}
+
+#[inline]
+fn is_identifier_byte(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b == b'_' || b >= 0x80
+}
+
+impl<'a> Scanner<'a> {
+    #[inline]
+    fn starts_with(&self, pattern: &[u8]) -> bool {
+        self.bytes[self.p..].starts_with(pattern)
+    }
+
+    /// Push the content of a string literal. In Rust, the `{name}` arguments of format strings are left out, because they are identifiers.
+    fn push_string(&mut self, range: Range<usize>) {
+        if self.language != Language::Rust {
+            self.strings.push(range);
+
+            return;
+        }
+
+        let bytes = self.bytes;
+        let mut start = range.start;
+        let mut p = range.start;
+
+        while p < range.end {
+            if bytes[p..range.end].starts_with(b"{{") {
+                p += 2;
+            } else if bytes[p] == b'{' {
+                let name_length = bytes[(p + 1)..range.end]
+                    .iter()
+                    .take_while(|&&b| is_identifier_byte(b))
+                    .count();
+
+                let argument_end = if name_length > 0 {
+                    bytes[(p + 1 + name_length)..range.end]
+                        .iter()
+                        .position(|&b| b == b'}' || b == b'{')
+                        .map(|i| p + 1 + name_length + i)
+                        .filter(|&i| {
+                            bytes[i] == b'}'
+                                && (i == p + 1 + name_length || bytes[p + 1 + name_length] == b':')
+                        })
+                } else {
+                    None
+                };
+
+                match argument_end {
+                    Some(argument_end) => {
+                        self.strings.push(start..p);
+
+                        p = argument_end + 1;
+                        start = p;
+                    },
+                    None => p += 1,
+                }
+            } else {
+                p += 1;
+            }
+        }
+
+        self.strings.push(start..range.end);
+    }
+
+    /// Find `closing` from `p` (skipping escaped characters if `escapes` is set), and push the string content before it.
+    fn string_until(&mut self, closing: &[u8], escapes: bool) {
+        let start = self.p;
+
+        while self.p < self.bytes.len() {
+            if escapes && self.bytes[self.p] == b'\\' {
+                self.p += 2;
+            } else if self.starts_with(closing) {
+                self.push_string(start..self.p);
+                self.p += closing.len();
+
+                return;
+            } else {
+                self.p += 1;
+            }
+        }
+
+        self.p = self.bytes.len();
+        self.push_string(start..self.p);
+    }
+
+    /// Scan a JavaScript template literal or a Python f-string from `p` to its end, or to the start of an embedded expression.
+    fn interpolated_string(&mut self, interpolation: Interpolation) {
+        let start = self.p;
+
+        while self.p < self.bytes.len() {
+            if interpolation.escapes && self.bytes[self.p] == b'\\' {
+                self.p += 2;
+            } else if self.starts_with(interpolation.closing) {
+                self.strings.push(start..self.p);
+                self.p += interpolation.closing.len();
+
+                return;
+            } else if interpolation.opening == b"{" && self.starts_with(b"{{") {
+                // an escaped brace of Python
+                self.p += 2;
+            } else if self.starts_with(interpolation.opening) {
+                self.strings.push(start..self.p);
+                self.p += interpolation.opening.len();
+                self.template.push((0, interpolation));
+
+                return;
+            } else {
+                self.p += 1;
+            }
+        }
+
+        self.p = self.bytes.len();
+        self.strings.push(start..self.p);
+    }
+
+    /// Whether a `/` at `p` starts a regular expression literal of JavaScript, judged by the code before it.
+    fn regex_allowed(&self) -> bool {
+        let before = &self.bytes[..self.p];
+        let end = before.iter().rposition(|b| !b.is_ascii_whitespace()).map(|i| i + 1).unwrap_or(0);
+
+        match before[..end].last() {
+            None => true,
+            Some(&b) if is_identifier_byte(b) => {
+                let start = before[..end]
+                    .iter()
+                    .rposition(|&b| !is_identifier_byte(b))
+                    .map(|i| i + 1)
+                    .unwrap_or(0);
+
+                REGEX_KEYWORDS.contains(&&before[start..end])
+            },
+            Some(b')') | Some(b']') | Some(b'}') | Some(b'"') | Some(b'\'') | Some(b'`') => false,
+            Some(_) => true,
+        }
+    }
+
+    /// Skip a regular expression literal of JavaScript which starts at `p`.
+    fn regex(&mut self) {
+        let bytes = self.bytes;
+        let mut in_class = false;
+
+        self.p += 1;
+
+        while self.p < bytes.len() {
+            match bytes[self.p] {
+                b'\\' => self.p += 1,
+                b'[' => in_class = true,
+                b']' => in_class = false,
+                b'/' if !in_class => {
+                    self.p += 1;
+
+                    return;
+                },
+                b'\n' => return,
+                _ => (),
+            }
+
+            self.p += 1;
+        }
+    }
+
+    fn line_comment(&mut self, marker_length: usize) {
+        let start = self.p + marker_length;
+
+        let mut end = self.bytes[start..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map(|i| start + i)
+            .unwrap_or(self.bytes.len());
+
+        self.p = end;
+
+        if end > start && self.bytes[end - 1] == b'\r' {
+            end -= 1;
+        }
+
+        self.comments.push(start..end);
+    }
+
+    fn block_comment(&mut self) {
+        let start = self.p + 2;
+        let nested = self.language == Language::Rust;
+
+        let mut depth = 1;
+
+        self.p = start;
+
+        while self.p < self.bytes.len() {
+            if self.starts_with(b"*/") {
+                depth -= 1;
+
+                if depth == 0 {
+                    self.comments.push(start..self.p);
+                    self.p += 2;
+
+                    return;
+                }
+
+                self.p += 2;
+            } else if nested && self.starts_with(b"/*") {
+                depth += 1;
+                self.p += 2;
+            } else {
+                self.p += 1;
+            }
+        }
+
+        self.comments.push(start..self.p);
+    }
+
+    /// Skip a character literal (or a Rust lifetime) which starts at `p`.
+    fn character(&mut self) {
+        let bytes = self.bytes;
+
+        self.p += 1;
+
+        if self.p >= bytes.len() {
+            return;
+        }
+
+        if bytes[self.p] == b'\\' {
+            while self.p < bytes.len() && bytes[self.p] != b'\'' && bytes[self.p] != b'\n' {
+                self.p += if bytes[self.p] == b'\\' { 2 } else { 1 };
+            }
+
+            self.p += 1;
+
+            return;
+        }
+
+        // the length of the UTF-8 character
+        let length = match bytes[self.p] {
+            b if b >= 0xF0 => 4,
+            b if b >= 0xE0 => 3,
+            b if b >= 0xC0 => 2,
+            _ => 1,
+        };
+
+        if bytes.get(self.p + length) == Some(&b'\'') {
+            self.p += length + 1;
+        }
+    }
+
+    /// Scan a Python string with a prefix like `f` or `rb`, whose quote is at `p`.
+    fn python_string(&mut self, prefix: &[u8]) {
+        let b = self.bytes[self.p];
+        let raw = prefix.iter().any(|b| b.eq_ignore_ascii_case(&b'r'));
+        let formatted = prefix.iter().any(|b| b.eq_ignore_ascii_case(&b'f'));
+
+        let closing: &'static [u8] = match (b, self.starts_with(&[b, b, b])) {
+            (b'"', true) => b"\"\"\"",
+            (b'"', false) => b"\"",
+            (_, true) => b"'''",
+            (_, false) => b"'",
+        };
+
+        self.p += closing.len();
+
+        if formatted {
+            self.interpolated_string(Interpolation {
+                closing,
+                opening: b"{",
+                escapes: !raw,
+            });
+        } else {
+            self.string_until(closing, !raw);
+        }
+    }
+
+    /// Skip an identifier or a number, and scan the raw string literal it starts, if any.
+    fn identifier(&mut self) {
+        let bytes = self.bytes;
+        let start = self.p;
+        let is_number = bytes[start].is_ascii_digit();
+
+        while self.p < bytes.len()
+            && (is_identifier_byte(bytes[self.p])
+                // digit separators of C++14
+                || (is_number && self.language == Language::C && bytes[self.p] == b'\''))
+        {
+            self.p += 1;
+        }
+
+        let identifier = &bytes[start..self.p];
+
+        match self.language {
+            Language::Rust if matches!(identifier, b"r" | b"br" | b"cr") => {
+                let hashes = bytes[self.p..].iter().take_while(|&&b| b == b'#').count();
+
+                if bytes.get(self.p + hashes) == Some(&b'"') {
+                    let mut closing = vec![b'"'];
+                    closing.resize(hashes + 1, b'#');
+
+                    self.p += hashes + 1;
+                    self.string_until(&closing, false);
+                }
+            },
+            Language::C if identifier.ends_with(b"R") && bytes.get(self.p) == Some(&b'"') => {
+                let delimiter_start = self.p + 1;
+
+                if let Some(i) = bytes[delimiter_start..].iter().position(|&b| b == b'(') {
+                    let delimiter = &bytes[delimiter_start..(delimiter_start + i)];
+
+                    let mut closing = vec![b')'];
+                    closing.extend_from_slice(delimiter);
+                    closing.push(b'"');
+
+                    self.p = delimiter_start + i + 1;
+                    self.string_until(&closing, false);
+                }
+            },
+            Language::Python
+                if identifier.len() <= 2
+                    && identifier.iter().all(|b| b"rRbBuUfF".contains(b))
+                    && matches!(bytes.get(self.p), Some(b'"') | Some(b'\'')) =>
+            {
+                self.python_string(identifier);
+            },
+            _ => (),
+        }
+    }
+
+    fn scan(&mut self) {
+        let language = self.language;
+
+        while self.p < self.bytes.len() {
+            let b = self.bytes[self.p];
+
+            match b {
+                b'#' if language == Language::Python => self.line_comment(1),
+                b'/' if language != Language::Python && self.starts_with(b"//") => {
+                    self.line_comment(2)
+                },
+                b'/' if language != Language::Python && self.starts_with(b"/*") => {
+                    self.block_comment()
+                },
+                b'/' if language == Language::JavaScript && self.regex_allowed() => self.regex(),
+                b'"' | b'\''
+                    if matches!(language, Language::Python | Language::Java)
+                        && self.starts_with(&[b, b, b]) =>
+                {
+                    self.p += 3;
+                    self.string_until(&[b, b, b], true);
+                },
+                b'"' => {
+                    self.p += 1;
+                    self.string_until(b"\"", true);
+                },
+                b'\'' if matches!(language, Language::Python | Language::JavaScript) => {
+                    self.p += 1;
+                    self.string_until(b"'", true);
+                },
+                b'\'' => self.character(),
+                b'`' if language == Language::Go => {
+                    self.p += 1;
+                    self.string_until(b"`", false);
+                },
+                b'`' if language == Language::JavaScript => {
+                    self.p += 1;
+                    self.interpolated_string(TEMPLATE_LITERAL);
+                },
+                b'{' if !self.template.is_empty() => {
+                    self.template.last_mut().unwrap().0 += 1;
+                    self.p += 1;
+                },
+                b'}' if !self.template.is_empty() => {
+                    self.p += 1;
+
+                    match self.template.last_mut().unwrap() {
+                        (0, interpolation) => {
+                            let interpolation = *interpolation;
+
+                            self.template.pop();
+                            self.interpolated_string(interpolation);
+                        },
+                        (depth, _) => *depth -= 1,
+                    }
+                },
+                b if is_identifier_byte(b) => self.identifier(),
+                _ => self.p += 1,
+            }
+        }
+    }
+}
+
+/// Convert the comments and the string literals of source code, keeping everything else (including identifiers) byte-for-byte.
+///
+/// Raw strings, Python and Java text blocks, Go raw strings, JavaScript template literals and Python f-strings (but not the expressions embedded in them) are recognized, and so are JavaScript regular expression literals, which are kept. The `{name}` arguments in Rust strings (for format strings like `format!("{name}")`) are kept, too. Character literals are never converted, because a conversion could turn one character into several. If `options.comments_only` is set, string literals are kept as well.
+pub fn convert_source<S: AsRef<str>>(
+    opencc: &OpenCC,
+    source: S,
+    language: Language,
+    options: &SourceOptions,
+) -> String {
+    let source = source.as_ref();
+
+    let mut scanner = Scanner {
+        bytes: source.as_bytes(),
+        language,
+        p: 0,
+        comments: Vec::new(),
+        strings: Vec::new(),
+        template: Vec::new(),
+    };
+
+    scanner.scan();
+
+    let mut ranges = scanner.comments;
+
+    if !options.comments_only {
+        ranges.extend(scanner.strings);
+    }
+
+    let replacements = ranges
+        .into_iter()
+        .filter_map(|range| {
+            let range = range.start..range.end.min(source.len());
+            let text = source.get(range.clone())?;
+
+            let converted = opencc.convert_in_document(text);
+
+            if converted != text {
+                Some((range, converted))
+            } else {
+                None
+            }
+        })
+        .collect();
+
+    apply_replacements(source, replacements)
+}
@@ -0,0 +1,465 @@
+use std::{
+    collections::{hash_map::RandomState, HashMap},
+    env,
+    fs::{self, DirBuilder, OpenOptions},
+    hash::{BuildHasher, Hasher},
+    io::{self, Write},
+    ops::Range,
+    path::{Path, PathBuf},
+    process,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+};
+
+use crate::{changes::map_range, find_changes, Change, DefaultConfig, OpenCC};
+
+/// How a dictionary of a conversion chain matched the text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DictionaryMatches {
+    /// The file name of the dictionary, such as `STPhrases.ocd2`.
+    pub dictionary: &'static str,
+    /// The number of runs of adjacent characters converted by the dictionary.
+    pub segments:   usize,
+    /// The number of characters converted by the dictionary.
+    pub characters: usize,
+}
+
+/// The statistics of conversions collected by `DictionaryProfiler`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConversionStatistics {
+    /// The dictionaries in the order of the conversion chain.
+    pub dictionaries:         Vec<DictionaryMatches>,
+    /// The number of CJK ideographs of the source.
+    pub cjk_characters:       usize,
+    /// The number of CJK ideographs which no dictionary changed. They include the ideographs which a dictionary maps to themselves, because the matches of OpenCC are not exposed.
+    pub unchanged_characters: usize,
+    /// How many times each unchanged CJK ideograph occurs.
+    pub unchanged:            HashMap<char, usize>,
+}
+
+impl ConversionStatistics {
+    /// Add the statistics of another conversion with the same conversion chain.
+    pub fn merge(&mut self, other: &ConversionStatistics) {
+        if self.dictionaries.is_empty() {
+            self.dictionaries = other
+                .dictionaries
+                .iter()
+                .map(|matches| DictionaryMatches {
+                    dictionary: matches.dictionary,
+                    segments:   0,
+                    characters: 0,
+                })
+                .collect();
+        }
+
+        for (matches, other) in self.dictionaries.iter_mut().zip(other.dictionaries.iter()) {
+            matches.segments += other.segments;
+            matches.characters += other.characters;
+        }
+
+        self.cjk_characters += other.cjk_characters;
+        self.unchanged_characters += other.unchanged_characters;
+
+        for (&c, &count) in other.unchanged.iter() {
+            *self.unchanged.entry(c).or_insert(0) += count;
+        }
+    }
+}
+
+/// The segmentation dictionary and the conversion chain (groups of dictionaries) of a default config.
+fn conversion_chain(config: DefaultConfig) -> (&'static str, &'static [&'static [&'static str]]) {
+    match config {
+        DefaultConfig::HK2S => ("TSPhrases.ocd2", &[
+            &["HKVariantsRevPhrases.ocd2", "HKVariantsRev.ocd2"],
+            &["TSPhrases.ocd2", "TSCharacters.ocd2"],
+        ]),
+        DefaultConfig::HK2T => {
+            ("HKVariantsRevPhrases.ocd2", &[&["HKVariantsRevPhrases.ocd2", "HKVariantsRev.ocd2"]])
+        },
+        DefaultConfig::JP2T => ("JPShinjitaiPhrases.ocd2", &[&[
+            "JPShinjitaiPhrases.ocd2",
+            "JPShinjitaiCharacters.ocd2",
+            "JPVariantsRev.ocd2",
+        ]]),
+        DefaultConfig::S2HK => {
+            ("STPhrases.ocd2", &[&["STPhrases.ocd2", "STCharacters.ocd2"], &["HKVariants.ocd2"]])
+        },
+        DefaultConfig::S2T => ("STPhrases.ocd2", &[&["STPhrases.ocd2", "STCharacters.ocd2"]]),
+        DefaultConfig::S2TW => {
+            ("STPhrases.ocd2", &[&["STPhrases.ocd2", "STCharacters.ocd2"], &["TWVariants.ocd2"]])
+        },
+        DefaultConfig::S2TWP => {
+            ("STPhrases.ocd2", &[&["STPhrases.ocd2", "STCharacters.ocd2"], &["TWPhrases.ocd2"], &[
+                "TWVariants.ocd2",
+            ]])
+        },
+        DefaultConfig::T2HK => ("HKVariants.ocd2", &[&["HKVariants.ocd2"]]),
+        DefaultConfig::T2JP => ("JPVariants.ocd2", &[&["JPVariants.ocd2"]]),
+        DefaultConfig::T2S => ("TSPhrases.ocd2", &[&["TSPhrases.ocd2", "TSCharacters.ocd2"]]),
+        DefaultConfig::T2TW => ("TWVariants.ocd2", &[&["TWVariants.ocd2"]]),
+        DefaultConfig::TW2S => ("TSPhrases.ocd2", &[
+            &["TWVariantsRevPhrases.ocd2", "TWVariantsRev.ocd2"],
+            &["TSPhrases.ocd2", "TSCharacters.ocd2"],
+        ]),
+        DefaultConfig::TW2SP => ("TSPhrases.ocd2", &[
+            &["TWPhrasesRev.ocd2", "TWVariantsRevPhrases.ocd2", "TWVariantsRev.ocd2"],
+            &["TSPhrases.ocd2", "TSCharacters.ocd2"],
+        ]),
+        DefaultConfig::TW2T => {
+            ("TWVariantsRevPhrases.ocd2", &[&["TWVariantsRevPhrases.ocd2", "TWVariantsRev.ocd2"]])
+        },
+    }
+}
+
+static TEMPORARY_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// A private directory (only accessible by the owner on Unix) in the temporary directory, which is removed with its files on drop.
+struct TemporaryDirectory {
+    path: PathBuf,
+}
+
+impl TemporaryDirectory {
+    /// Create a new directory with an unpredictable name, so that no existing directory or link is ever used.
+    fn create() -> io::Result<TemporaryDirectory> {
+        loop {
+            // `RandomState` is seeded randomly for each process and each instance
+            let mut hasher = RandomState::new().build_hasher();
+
+            hasher.write_u32(process::id());
+            hasher.write_usize(TEMPORARY_COUNTER.fetch_add(1, Ordering::Relaxed));
+
+            let path = env::temp_dir().join(format!("opencc-rust-{:016x}", hasher.finish()));
+
+            let mut builder = DirBuilder::new();
+
+            #[cfg(unix)]
+            {
+                use std::os::unix::fs::DirBuilderExt;
+
+                builder.mode(0o700);
+            }
+
+            match builder.create(&path) {
+                Ok(()) => {
+                    return Ok(TemporaryDirectory {
+                        path,
+                    })
+                },
+                Err(error) if error.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(error) => return Err(error),
+            }
+        }
+    }
+
+    /// Write a new file in the directory.
+    fn write(&self, file_name: &str, content: &str) -> io::Result<PathBuf> {
+        let path = self.path.join(file_name);
+
+        OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)?
+            .write_all(content.as_bytes())?;
+
+        Ok(path)
+    }
+}
+
+impl Drop for TemporaryDirectory {
+    #[inline]
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Open an OpenCC instance whose conversion chain is one group of dictionaries, through a config file in `temporary`. `directory` is the absolute path of the directory of the dictionaries.
+fn open_group(
+    temporary: &TemporaryDirectory,
+    segmentation: &str,
+    dictionaries: &[&str],
+    directory: &Path,
+) -> Result<OpenCC, &'static str> {
+    let dictionary = |file_name: &str| {
+        format!(
+            "{{\"type\": \"ocd2\", \"file\": {:?}}}",
+            directory.join(file_name).to_string_lossy()
+        )
+    };
+
+    let group = if dictionaries.len() == 1 {
+        dictionary(dictionaries[0])
+    } else {
+        let dictionaries: Vec<String> = dictionaries.iter().map(|d| dictionary(d)).collect();
+
+        format!("{{\"type\": \"group\", \"dicts\": [{}]}}", dictionaries.join(", "))
+    };
+
+    let config = format!(
+        "{{\"name\": \"opencc-rust\", \"segmentation\": {{\"type\": \"mmseg\", \"dict\": {}}}, \
+         \"conversion_chain\": [{{\"dict\": {}}}]}}",
+        dictionary(segmentation),
+        group
+    );
+
+    let path = temporary
+        .write(&format!("{}.json", TEMPORARY_COUNTER.fetch_add(1, Ordering::Relaxed)), &config)
+        .map_err(|_| "Cannot write a temporary config file.")?;
+
+    // the dictionaries are loaded when the instance is created, so the directory can be removed afterwards
+    OpenCC::new(path)
+}
+
+#[inline]
+fn is_ideograph(c: char) -> bool {
+    matches!(c as u32, 0x3400..=0x4DBF | 0x4E00..=0x9FFF | 0xF900..=0xFAFF | 0x20000..=0x3FFFF)
+}
+
+#[inline]
+fn overlaps(a: &Range<usize>, b: &Range<usize>) -> bool {
+    a.start < b.end && b.start < a.end || a == b
+}
+
+struct Stage {
+    /// The index of the first dictionary of the group in `ConversionStatistics::dictionaries`.
+    first:        usize,
+    group:        OpenCC,
+    /// An instance for each dictionary of the group, if it has more than one.
+    dictionaries: Vec<OpenCC>,
+}
+
+/// Convert text with a default config and count which dictionaries of its conversion chain converted what.
+///
+/// The OpenCC library does not expose its matches, so each step of the chain is run again with temporary configs which have only one of its dictionaries, and every change is attributed to the first dictionary of the step which makes it. A segment is a run of adjacent characters converted by one dictionary. A match which does not change the text is not counted, so a character with only such an entry is reported as unchanged.
+///
+/// The temporary configs are written to a private temporary directory, and refer to the dictionaries by absolute paths.
+pub struct DictionaryProfiler {
+    opencc:     OpenCC,
+    stages:     Vec<Stage>,
+    statistics: Mutex<ConversionStatistics>,
+}
+
+impl DictionaryProfiler {
+    /// Create a profiler for a default config, whose dictionaries are in the data directory of OpenCC, as found when this crate was built (see `OPENCC_DATA_DIR`).
+    #[inline]
+    pub fn new(config: DefaultConfig) -> Result<DictionaryProfiler, &'static str> {
+        Self::open(config, None)
+    }
+
+    /// Create a profiler for a default config, whose dictionaries are in `directory`, such as the one `generate_static_dictionary` writes to.
+    #[inline]
+    pub fn with_dictionary_directory<P: AsRef<Path>>(
+        config: DefaultConfig,
+        directory: P,
+    ) -> Result<DictionaryProfiler, &'static str> {
+        Self::open(config, Some(directory.as_ref()))
+    }
+
+    fn open(
+        config: DefaultConfig,
+        directory: Option<&Path>,
+    ) -> Result<DictionaryProfiler, &'static str> {
+        let opencc = match directory {
+            Some(directory) => OpenCC::new(directory.join(config.get_file_name()))?,
+            None => OpenCC::new(config)?,
+        };
+
+        let directory = match directory {
+            Some(directory) => directory
+                .canonicalize()
+                .map_err(|_| "Cannot find the directory of the dictionaries.")?,
+            None => match option_env!("OPENCC_RUST_DATA_DIR") {
+                Some(directory) if Path::new(directory).is_absolute() => PathBuf::from(directory),
+                _ => {
+                    return Err("The data directory of OpenCC is unknown. Use \
+                                `DictionaryProfiler::with_dictionary_directory`, or set \
+                                `OPENCC_DATA_DIR` when building.")
+                },
+            },
+        };
+
+        let temporary =
+            TemporaryDirectory::create().map_err(|_| "Cannot create a temporary directory.")?;
+
+        let (segmentation, chain) = conversion_chain(config);
+
+        let mut stages = Vec::with_capacity(chain.len());
+        let mut dictionaries = Vec::new();
+
+        for group in chain.iter() {
+            stages.push(Stage {
+                first:        dictionaries.len(),
+                group:        open_group(&temporary, segmentation, group, &directory)?,
+                dictionaries: if group.len() > 1 {
+                    group
+                        .iter()
+                        .map(|dictionary| {
+                            open_group(&temporary, segmentation, &[dictionary], &directory)
+                        })
+                        .collect::<Result<_, _>>()?
+                } else {
+                    Vec::new()
+                },
+            });
+
+            dictionaries.extend(group.iter().map(|&dictionary| DictionaryMatches {
+                dictionary,
+                segments: 0,
+                characters: 0,
+            }));
+        }
+
+        Ok(DictionaryProfiler {
+            opencc,
+            stages,
+            statistics: Mutex::new(ConversionStatistics {
+                dictionaries,
+                ..ConversionStatistics::default()
+            }),
+        })
+    }
+
+    /// Convert a string, and return the statistics of this conversion. They are also added to the statistics of the profiler.
+    pub fn convert_with_statistics<S: AsRef<str>>(
+        &self,
+        input: S,
+    ) -> (String, ConversionStatistics) {
+        let input = input.as_ref();
+
+        let mut statistics = ConversionStatistics {
+            dictionaries: self.statistics.lock().unwrap().dictionaries.clone(),
+            ..ConversionStatistics::default()
+        };
+
+        for matches in statistics.dictionaries.iter_mut() {
+            matches.segments = 0;
+            matches.characters = 0;
+        }
+
+        // the ranges of the input converted by any step
+        let mut covered: Vec<Range<usize>> = Vec::new();
+        // the changes of the previous steps, from their output back to their input
+        let mut reverse_changes: Vec<Vec<Change>> = Vec::new();
+
+        let mut text = input.to_string();
+
+        for stage in self.stages.iter() {
+            let converted = stage.group.convert(&text);
+            let changes = find_changes(&text, &converted);
+
+            let dictionary_changes: Vec<Vec<Change>> = stage
+                .dictionaries
+                .iter()
+                .map(|opencc| find_changes(&text, opencc.convert(&text)))
+                .collect();
+
+            for change in changes.iter() {
+                // the changes of a dictionary are not counted where an earlier dictionary of the group matched
+                let mut claimed: Vec<Range<usize>> = Vec::new();
+
+                for (index, changes) in dictionary_changes.iter().enumerate() {
+                    for c in
+                        changes.iter().filter(|c| overlaps(&c.source_range, &change.source_range))
+                    {
+                        let characters = c
+                            .from
+                            .char_indices()
+                            .map(|(i, _)| c.source_range.start + i)
+                            .filter(|i| !claimed.iter().any(|range| range.contains(i)))
+                            .count();
+
+                        if characters > 0 || c.from.is_empty() {
+                            let matches = &mut statistics.dictionaries[stage.first + index];
+
+                            matches.segments += 1;
+                            matches.characters += characters;
+                        }
+                    }
+
+                    claimed.extend(
+                        changes
+                            .iter()
+                            .filter(|c| overlaps(&c.source_range, &change.source_range))
+                            .map(|c| c.source_range.clone()),
+                    );
+                }
+
+                if claimed.is_empty() {
+                    let matches = &mut statistics.dictionaries
+                        [stage.first + dictionary_changes.len().saturating_sub(1)];
+
+                    matches.segments += 1;
+                    matches.characters += change.from.chars().count();
+                }
+
+                let mut range = change.source_range.clone();
+
+                for reverse in reverse_changes.iter().rev() {
+                    range = map_range(reverse, range);
+                }
+
+                covered.push(range);
+            }
+
+            reverse_changes.push(
+                changes
+                    .into_iter()
+                    .map(|change| Change {
+                        source_range: change.target_range,
+                        target_range: change.source_range,
+                        from:         change.to,
+                        to:           change.from,
+                    })
+                    .collect(),
+            );
+
+            text = converted;
+        }
+
+        covered.sort_unstable_by_key(|range| range.start);
+
+        let mut covered = covered.into_iter().peekable();
+        let mut covered_end = 0;
+
+        for (i, c) in input.char_indices() {
+            while let Some(range) = covered.next_if(|range| range.start <= i) {
+                covered_end = covered_end.max(range.end);
+            }
+
+            if is_ideograph(c) {
+                statistics.cjk_characters += 1;
+
+                if i >= covered_end {
+                    statistics.unchanged_characters += 1;
+
+                    *statistics.unchanged.entry(c).or_insert(0) += 1;
+                }
+            }
+        }
+
+        self.statistics.lock().unwrap().merge(&statistics);
+
+        (self.opencc.convert(input), statistics)
+    }
+
+    /// Get the statistics of all the conversions of the profiler.
+    #[inline]
+    pub fn statistics(&self) -> ConversionStatistics {
+        self.statistics.lock().unwrap().clone()
+    }
+
+    /// Clear the statistics of the profiler.
+    pub fn reset_statistics(&self) {
+        let mut statistics = self.statistics.lock().unwrap();
+
+        for matches in statistics.dictionaries.iter_mut() {
+            matches.segments = 0;
+            matches.characters = 0;
+        }
+
+        statistics.cjk_characters = 0;
+        statistics.unchanged_characters = 0;
+        statistics.unchanged.clear();
+    }
+}
@@ -0,0 +1,170 @@
+use std::ops::Range;
+
+use crate::{
+    segments::{apply_replacements, convert_segments, lines},
+    OpenCC,
+};
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Markup {
+    /// `<i>` tags and `{\an8}` positioning tags.
+    Srt,
+    /// `<v Speaker>`, `<c.class>` and timestamp tags.
+    Vtt,
+    /// `{...}` override blocks and the `\N`, `\n` and `\h` escapes.
+    Ass,
+}
+
+/// Push the ranges of the text in `range`, leaving out the markup.
+fn push_text_ranges(
+    source: &str,
+    range: Range<usize>,
+    markup: Markup,
+    segments: &mut Vec<Range<usize>>,
+) {
+    let bytes = source.as_bytes();
+
+    let mut text_start = range.start;
+    let mut p = range.start;
+
+    while p < range.end {
+        let markup_end = match bytes[p] {
+            b'<' if markup != Markup::Ass => source[p..range.end].find('>').map(|i| p + i + 1),
+            b'{' if markup != Markup::Vtt => source[p..range.end].find('}').map(|i| p + i + 1),
+            b'\\'
+                if markup == Markup::Ass
+                    && matches!(bytes.get(p + 1), Some(b'N') | Some(b'n') | Some(b'h')) =>
+            {
+                Some(p + 2)
+            },
+            _ => None,
+        };
+
+        match markup_end {
+            Some(markup_end) => {
+                if text_start < p {
+                    segments.push(text_start..p);
+                }
+
+                p = markup_end;
+                text_start = markup_end;
+            },
+            None => p += 1,
+        }
+    }
+
+    if text_start < range.end {
+        segments.push(text_start..range.end);
+    }
+}
+
+/// Convert the pending text ranges as one unit.
+fn flush(
+    opencc: &OpenCC,
+    source: &str,
+    segments: &mut Vec<Range<usize>>,
+    replacements: &mut Vec<(Range<usize>, String)>,
+) {
+    let texts: Vec<&str> = segments.iter().map(|range| &source[range.clone()]).collect();
+
+    let converted = convert_segments(opencc, &texts);
+
+    for (range, converted) in segments.drain(..).zip(converted) {
+        if source[range.clone()] != converted {
+            replacements.push((range, converted));
+        }
+    }
+}
+
+/// Convert the text of the cues (the lines after a timing line, up to a blank line).
+fn convert_cues(opencc: &OpenCC, source: &str, markup: Markup) -> String {
+    let mut replacements = Vec::new();
+    let mut segments = Vec::new();
+
+    let mut in_text = false;
+
+    for line in lines(source) {
+        let text = &source[line.clone()];
+
+        if text.trim().is_empty() {
+            flush(opencc, source, &mut segments, &mut replacements);
+
+            in_text = false;
+        } else if in_text {
+            push_text_ranges(source, line, markup, &mut segments);
+        } else if text.contains("-->") {
+            in_text = true;
+        }
+    }
+
+    flush(opencc, source, &mut segments, &mut replacements);
+
+    apply_replacements(source, replacements)
+}
+
+/// Convert the dialogue text of a SubRip (SRT) subtitle file.
+///
+/// Cue numbers, timestamps and tags (`<i>`, `{\an8}`) are kept, and so are the line endings and the BOM. The lines of a cue are converted as one unit.
+pub fn convert_srt<S: AsRef<str>>(opencc: &OpenCC, srt: S) -> String {
+    convert_cues(opencc, srt.as_ref(), Markup::Srt)
+}
+
+/// Convert the cue text of a WebVTT subtitle file.
+///
+/// The header, `NOTE`, `STYLE` and `REGION` blocks, cue identifiers, timestamps, cue settings and tags (`<v Speaker>`, `<c.class>`, timestamp tags) are kept, and so are the line endings and the BOM. The lines of a cue are converted as one unit.
+pub fn convert_vtt<S: AsRef<str>>(opencc: &OpenCC, vtt: S) -> String {
+    convert_cues(opencc, vtt.as_ref(), Markup::Vtt)
+}
+
+/// Convert the dialogue text of an Advanced SubStation Alpha (ASS) or SubStation Alpha (SSA) subtitle file.
+///
+/// Only the `Text` field of the `Dialogue:` lines in the `[Events]` section is converted. Override blocks (`{\i1}`) and the `\N`, `\n` and `\h` escapes are kept, and a phrase broken by them is still converted as a whole. The line endings and the BOM are kept.
+pub fn convert_ass<S: AsRef<str>>(opencc: &OpenCC, ass: S) -> String {
+    let ass = ass.as_ref();
+
+    let mut replacements = Vec::new();
+    let mut segments = Vec::new();
+
+    let mut in_events = false;
+    // the index of the `Text` field, which is the last of the ten default fields
+    let mut text_field = 9;
+
+    for line in lines(ass) {
+        let original = &ass[line.clone()];
+        let text = original.trim_start_matches('\u{feff}').trim_start();
+
+        if text.starts_with('[') {
+            in_events = text.trim_end().eq_ignore_ascii_case("[events]");
+
+            continue;
+        }
+
+        if !in_events {
+            continue;
+        }
+
+        if let Some(fields) = text.strip_prefix("Format:") {
+            if let Some(index) =
+                fields.split(',').position(|field| field.trim().eq_ignore_ascii_case("text"))
+            {
+                text_field = index;
+            }
+        } else if let Some(fields) = text.strip_prefix("Dialogue:") {
+            let fields_start = line.end - fields.len();
+
+            let text_start = if text_field == 0 {
+                Some(fields_start)
+            } else {
+                fields.match_indices(',').nth(text_field - 1).map(|(i, _)| fields_start + i + 1)
+            };
+
+            if let Some(text_start) = text_start {
+                push_text_ranges(ass, text_start..line.end, Markup::Ass, &mut segments);
+            }
+
+            flush(opencc, ass, &mut segments, &mut replacements);
+        }
+    }
+
+    apply_replacements(ass, replacements)
+}
@@ -0,0 +1,206 @@
+use std::ops::Range;
+
+use unicode_normalization::{
+    char::{canonical_combining_class, decompose_canonical, decompose_compatible},
+    UnicodeNormalization as _,
+};
+
+/// The policy of the Unicode normalization stage. See `OpenCC::set_unicode_normalization`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnicodeNormalization {
+    /// Compose the text to NFC, such as `e` + U+0301 to `é`.
+    pub nfc:                      bool,
+    /// Fold CJK compatibility ideographs (U+F900 to U+FAFF and U+2F800 to U+2FA1F), such as `說` (U+F96F) to `說` (U+8AAA). Without it, they are kept even if `nfc` is set.
+    pub compatibility_ideographs: bool,
+    /// Fold Kangxi radicals (`⾔`) and CJK radicals which have a unified ideograph form (`⻌`), such as `⾔` to `言`.
+    pub radicals:                 bool,
+}
+
+/// A character (or a character with its combining marks) changed by `UnicodeNormalization::apply_with_report`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NormalizedCharacter {
+    /// The byte range of the input.
+    pub range: Range<usize>,
+    pub from:  String,
+    pub to:    String,
+}
+
+/// CJK radicals (U+2E80 to U+2EF3) which stand for a unified ideograph, sorted. Most of them have no compatibility decomposition.
+const RADICALS: [(char, char); 50] = [
+    ('⺅', '亻'),
+    ('⺉', '刂'),
+    ('⺖', '忄'),
+    ('⺘', '扌'),
+    ('⺝', '月'),
+    ('⺟', '母'),
+    ('⺠', '民'),
+    ('⺡', '氵'),
+    ('⺣', '灬'),
+    ('⺧', '牜'),
+    ('⺨', '犭'),
+    ('⺩', '王'),
+    ('⺫', '罒'),
+    ('⺬', '示'),
+    ('⺭', '礻'),
+    ('⺺', '聿'),
+    ('⺼', '肉'),
+    ('⺾', '艹'),
+    ('⻂', '衤'),
+    ('⻄', '西'),
+    ('⻅', '见'),
+    ('⻈', '讠'),
+    ('⻉', '贝'),
+    ('⻊', '𧾷'),
+    ('⻋', '车'),
+    ('⻌', '辶'),
+    ('⻍', '辶'),
+    ('⻎', '辶'),
+    ('⻏', '阝'),
+    ('⻐', '钅'),
+    ('⻑', '長'),
+    ('⻓', '长'),
+    ('⻔', '门'),
+    ('⻖', '阝'),
+    ('⻗', '雨'),
+    ('⻘', '青'),
+    ('⻙', '韦'),
+    ('⻚', '页'),
+    ('⻛', '风'),
+    ('⻜', '飞'),
+    ('⻝', '食'),
+    ('⻢', '马'),
+    ('⻥', '鱼'),
+    ('⻦', '鸟'),
+    ('⻨', '麦'),
+    ('⻩', '黄'),
+    ('⻬', '齐'),
+    ('⻮', '齿'),
+    ('⻰', '龙'),
+    ('⻳', '龟'),
+];
+
+#[inline]
+fn is_compatibility_ideograph(c: char) -> bool {
+    matches!(c, '\u{F900}'..='\u{FAFF}' | '\u{2F800}'..='\u{2FA1F}')
+}
+
+/// Whether `c` can only continue the segment before it. Hangul vowel and trailing jamo have no combining class, but compose with the syllable before them.
+#[inline]
+fn continues_segment(c: char) -> bool {
+    canonical_combining_class(c) != 0 || matches!(c, '\u{1161}'..='\u{11C2}')
+}
+
+impl Default for UnicodeNormalization {
+    /// Every folding is enabled.
+    #[inline]
+    fn default() -> Self {
+        UnicodeNormalization {
+            nfc:                      true,
+            compatibility_ideographs: true,
+            radicals:                 true,
+        }
+    }
+}
+
+impl UnicodeNormalization {
+    fn fold_radical(c: char) -> Option<char> {
+        if ('\u{2F00}'..='\u{2FD5}').contains(&c) {
+            let mut folded = None;
+
+            decompose_compatible(c, |d| folded = Some(d));
+
+            return folded;
+        }
+
+        RADICALS.binary_search_by(|(radical, _)| radical.cmp(&c)).ok().map(|i| RADICALS[i].1)
+    }
+
+    /// Normalize a segment, which is a starter with the combining marks after it.
+    fn normalize_segment(&self, segment: &str, output: &mut String) {
+        let mut chars = segment.chars();
+        let first = chars.next().unwrap();
+        let is_single = chars.next().is_none();
+
+        if self.radicals && is_single {
+            if let Some(folded) = Self::fold_radical(first) {
+                output.push(folded);
+
+                return;
+            }
+        }
+
+        if is_compatibility_ideograph(first) {
+            if !self.compatibility_ideographs {
+                output.push(first);
+
+                if self.nfc {
+                    output.extend(segment[first.len_utf8()..].nfc());
+                } else {
+                    output.push_str(&segment[first.len_utf8()..]);
+                }
+
+                return;
+            }
+
+            if !self.nfc {
+                decompose_canonical(first, |d| output.push(d));
+                output.push_str(&segment[first.len_utf8()..]);
+
+                return;
+            }
+        }
+
+        if self.nfc {
+            output.extend(segment.nfc());
+        } else {
+            output.push_str(segment);
+        }
+    }
+
+    /// Apply the policy to a string.
+    pub fn apply<S: AsRef<str>>(&self, text: S) -> String {
+        self.apply_with_report(text).0
+    }
+
+    /// Apply the policy to a string, and also return which characters are normalized.
+    ///
+    /// Variation selectors, including the ideographic ones (U+E0100 to U+E01EF), are kept after the characters they follow.
+    pub fn apply_with_report<S: AsRef<str>>(&self, text: S) -> (String, Vec<NormalizedCharacter>) {
+        let text = text.as_ref();
+
+        let mut output = String::with_capacity(text.len());
+        let mut report = Vec::new();
+
+        let mut start = 0;
+
+        let boundaries = text
+            .char_indices()
+            .skip(1)
+            .filter(|&(_, c)| !continues_segment(c))
+            .map(|(i, _)| i)
+            .chain(Some(text.len()));
+
+        for end in boundaries {
+            if end == start {
+                continue;
+            }
+
+            let segment = &text[start..end];
+            let output_start = output.len();
+
+            self.normalize_segment(segment, &mut output);
+
+            if output[output_start..] != *segment {
+                report.push(NormalizedCharacter {
+                    range: start..end,
+                    from:  segment.to_string(),
+                    to:    output[output_start..].to_string(),
+                });
+            }
+
+            start = end;
+        }
+
+        (output, report)
+    }
+}
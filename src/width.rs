@@ -0,0 +1,203 @@
+use std::ops::Range;
+
+/// A character width to normalize to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Width {
+    Half,
+    Full,
+}
+
+/// The policy of the width normalization stage. See `OpenCC::set_width_normalization`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WidthNormalization {
+    /// Whether the stage runs before the conversion of the dictionaries, instead of after it.
+    pub before_conversion: bool,
+    /// Fold ASCII letters and digits (`ＡＢＣ１２３` and `ABC123`) to this width.
+    pub alphanumerics:     Option<Width>,
+    /// Fold half-width katakana (`ｶﾞ`) to full-width (`ガ`).
+    pub katakana:          bool,
+    /// Fold the ideographic space (U+3000) and the ASCII space to this width.
+    pub space:             Option<Width>,
+    /// Fold ASCII punctuation (`,!?:;()`) to this width. Folding to full width only happens next to CJK characters, so English text is kept.
+    pub punctuation:       Option<Width>,
+}
+
+/// Full-width characters of the half-width katakana block, from U+FF61 to U+FF9F.
+const KATAKANA: [char; 63] = [
+    '。', '「', '」', '、', '・', 'ヲ', 'ァ', 'ィ', 'ゥ', 'ェ', 'ォ', 'ャ', 'ュ', 'ョ', 'ッ', 'ー',
+    'ア', 'イ', 'ウ', 'エ', 'オ', 'カ', 'キ', 'ク', 'ケ', 'コ', 'サ', 'シ', 'ス', 'セ', 'ソ', 'タ',
+    'チ', 'ツ', 'テ', 'ト', 'ナ', 'ニ', 'ヌ', 'ネ', 'ノ', 'ハ', 'ヒ', 'フ', 'ヘ', 'ホ', 'マ', 'ミ',
+    'ム', 'メ', 'モ', 'ヤ', 'ユ', 'ヨ', 'ラ', 'リ', 'ル', 'レ', 'ロ', 'ワ', 'ン', '゛', '゜',
+];
+
+/// ASCII punctuation which has a full-width form commonly used in CJK text.
+const PUNCTUATION: [char; 7] = [',', '!', '?', ':', ';', '(', ')'];
+
+#[inline]
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32, 0x2E80..=0x2FFF | 0x3001..=0x9FFF | 0xF900..=0xFAFF | 0xFF00..=0xFFEF | 0x20000..=0x3FFFF)
+}
+
+/// Combine a full-width katakana with a following half-width (semi-)voiced sound mark.
+fn voiced_katakana(base: char, mark: char) -> Option<char> {
+    let code = base as u32;
+
+    let voiceable = matches!(code, 0x30AB..=0x30C2 if code % 2 == 1)
+        || matches!(code, 0x30C4 | 0x30C6 | 0x30C8)
+        || matches!(code, 0x30CF..=0x30DD if (code - 0x30CF) % 3 == 0);
+
+    match mark {
+        'ﾞ' if base == 'ウ' => Some('ヴ'),
+        'ﾞ' if voiceable => char::from_u32(code + 1),
+        'ﾟ' if matches!(code, 0x30CF..=0x30DD if (code - 0x30CF) % 3 == 0) => {
+            char::from_u32(code + 2)
+        },
+        _ => None,
+    }
+}
+
+impl Default for WidthNormalization {
+    /// The policy of Chinese text: full-width alphanumerics are folded to half width, half-width katakana to full width, and ASCII punctuation next to CJK characters to full width. Spaces are kept.
+    ///
+    /// Mainland China, Taiwan and Hong Kong write the widths of these characters in the same way, so there is no policy per region. Change the fields for another policy.
+    #[inline]
+    fn default() -> Self {
+        WidthNormalization {
+            before_conversion: false,
+            alphanumerics:     Some(Width::Half),
+            katakana:          true,
+            space:             None,
+            punctuation:       Some(Width::Full),
+        }
+    }
+}
+
+impl WidthNormalization {
+    /// Create the policy of Chinese text. See `WidthNormalization::default`.
+    #[inline]
+    pub fn new() -> WidthNormalization {
+        WidthNormalization::default()
+    }
+
+    /// Fold a character. In a document, no ASCII character is created, and only full-width letters and digits and half-width katakana are folded.
+    fn fold(&self, c: char, previous: Option<char>, next: Option<char>, in_document: bool) -> char {
+        let code = c as u32;
+
+        match self.alphanumerics {
+            Some(Width::Half) if matches!(code, 0xFF10..=0xFF19 | 0xFF21..=0xFF3A | 0xFF41..=0xFF5A) =>
+            {
+                return char::from_u32(code - 0xFEE0).unwrap_or(c);
+            },
+            Some(Width::Full) if c.is_ascii_alphanumeric() && !in_document => {
+                return char::from_u32(code + 0xFEE0).unwrap_or(c);
+            },
+            _ => (),
+        }
+
+        if in_document {
+            return c;
+        }
+
+        match self.space {
+            Some(Width::Half) if c == '\u{3000}' => return ' ',
+            Some(Width::Full) if c == ' ' => return '\u{3000}',
+            _ => (),
+        }
+
+        match self.punctuation {
+            Some(Width::Half) => {
+                if let Some(half) = char::from_u32(code.wrapping_sub(0xFEE0)) {
+                    if PUNCTUATION.contains(&half) {
+                        return half;
+                    }
+                }
+            },
+            Some(Width::Full)
+                if PUNCTUATION.contains(&c)
+                    && (previous.map(is_cjk).unwrap_or(false)
+                        || next.map(is_cjk).unwrap_or(false)) =>
+            {
+                return char::from_u32(code + 0xFEE0).unwrap_or(c);
+            },
+            _ => (),
+        }
+
+        c
+    }
+
+    /// Apply the policy to a string.
+    pub fn apply<S: AsRef<str>>(&self, text: S) -> String {
+        self.apply_with_offsets(text).0
+    }
+
+    /// Apply the policy to the text of a document (see `OpenCC::convert` for the document formats), without folding the characters which can be its syntax. Only full-width letters and digits (to half width) and half-width katakana are folded.
+    pub(crate) fn apply_in_document(&self, text: &str) -> String {
+        self.apply_inner(text, true, None, None).0
+    }
+
+    /// Apply the policy to a piece of a text, where `previous` and `next` are the characters around it. A half-width katakana at the end of the piece is not combined with `next`.
+    #[cfg(feature = "tokio")]
+    pub(crate) fn apply_piece(
+        &self,
+        text: &str,
+        previous: Option<char>,
+        next: Option<char>,
+    ) -> String {
+        self.apply_inner(text, false, previous, next).0
+    }
+
+    /// Apply the policy to a string, and also return, for each character of the output, the byte range of the characters of the input it comes from.
+    ///
+    /// Most characters map one to one, but a half-width katakana followed by a (semi-)voiced sound mark becomes one character.
+    pub fn apply_with_offsets<S: AsRef<str>>(&self, text: S) -> (String, Vec<Range<usize>>) {
+        self.apply_inner(text.as_ref(), false, None, None)
+    }
+
+    fn apply_inner(
+        &self,
+        text: &str,
+        in_document: bool,
+        before: Option<char>,
+        after: Option<char>,
+    ) -> (String, Vec<Range<usize>>) {
+        let mut output = String::with_capacity(text.len());
+        let mut offsets = Vec::new();
+
+        let chars: Vec<(usize, char)> = text.char_indices().collect();
+
+        let mut i = 0;
+
+        while i < chars.len() {
+            let (start, c) = chars[i];
+            let next = chars.get(i + 1).map(|&(_, c)| c);
+
+            let mut length = 1;
+
+            let folded = if self.katakana && ('\u{FF61}'..='\u{FF9F}').contains(&c) {
+                let full = KATAKANA[(c as u32 - 0xFF61) as usize];
+
+                match next.and_then(|mark| voiced_katakana(full, mark)) {
+                    Some(voiced) => {
+                        length = 2;
+
+                        voiced
+                    },
+                    None => full,
+                }
+            } else {
+                let previous = if i > 0 { Some(chars[i - 1].1) } else { before };
+
+                self.fold(c, previous, next.or(after), in_document)
+            };
+
+            let end = chars.get(i + length).map(|&(index, _)| index).unwrap_or(text.len());
+
+            output.push(folded);
+            offsets.push(start..end);
+
+            i += length;
+        }
+
+        (output, offsets)
+    }
+}
@@ -0,0 +1,120 @@
+use std::ops::Range;
+
+/// A token of an XML document. Comments, CDATA sections, processing instructions and declarations are `Other`.
+pub(crate) enum XmlToken<'a> {
+    Start { name: &'a str, self_closing: bool },
+    End { name: &'a str },
+    Text(Range<usize>),
+    Other,
+}
+
+/// A minimal XML tokenizer, which is enough to find the text of well-formed documents.
+pub(crate) struct XmlTokenizer<'a> {
+    source: &'a str,
+    p:      usize,
+}
+
+impl<'a> XmlTokenizer<'a> {
+    #[inline]
+    pub(crate) fn new(source: &'a str) -> Self {
+        XmlTokenizer {
+            source,
+            p: 0,
+        }
+    }
+
+    /// Find `pattern` after the current position, and move to the end of it (or to the end of the source).
+    fn skip_past(&mut self, pattern: &str) {
+        self.p = match self.source[self.p..].find(pattern) {
+            Some(i) => self.p + i + pattern.len(),
+            None => self.source.len(),
+        };
+    }
+
+    /// Find the end of the tag which starts at the current position, skipping quoted attribute values.
+    fn tag_end(&self) -> usize {
+        let bytes = self.source.as_bytes();
+
+        let mut quote = None;
+
+        for (i, &b) in bytes.iter().enumerate().skip(self.p) {
+            match quote {
+                Some(q) if b == q => quote = None,
+                Some(_) => (),
+                None => match b {
+                    b'"' | b'\'' => quote = Some(b),
+                    b'>' => return i + 1,
+                    _ => (),
+                },
+            }
+        }
+
+        bytes.len()
+    }
+}
+
+impl<'a> Iterator for XmlTokenizer<'a> {
+    type Item = XmlToken<'a>;
+
+    fn next(&mut self) -> Option<XmlToken<'a>> {
+        let source = self.source;
+        let rest = &source[self.p..];
+
+        if rest.is_empty() {
+            return None;
+        }
+
+        if !rest.starts_with('<') {
+            let start = self.p;
+
+            self.p = rest.find('<').map(|i| start + i).unwrap_or(source.len());
+
+            return Some(XmlToken::Text(start..self.p));
+        }
+
+        if rest.starts_with("<!--") {
+            self.skip_past("-->");
+
+            return Some(XmlToken::Other);
+        }
+
+        if rest.starts_with("<![CDATA[") {
+            self.skip_past("]]>");
+
+            return Some(XmlToken::Other);
+        }
+
+        if rest.starts_with("<?") {
+            self.skip_past("?>");
+
+            return Some(XmlToken::Other);
+        }
+
+        if rest.starts_with("<!") {
+            self.skip_past(">");
+
+            return Some(XmlToken::Other);
+        }
+
+        let start = self.p;
+        let end = self.tag_end();
+
+        self.p = end;
+
+        let tag = source[(start + 1)..end].trim_end_matches('>');
+
+        if let Some(name) = tag.strip_prefix('/') {
+            return Some(XmlToken::End {
+                name: name.trim()
+            });
+        }
+
+        let name_length =
+            tag.find(|c: char| c.is_whitespace() || c == '/' || c == '>').unwrap_or(tag.len());
+
+        Some(XmlToken::Start {
+            name:         &tag[..name_length],
+            self_closing: tag.ends_with('/'),
+        })
+    }
+}
@@ -0,0 +1,76 @@
+#![cfg(feature = "tokio")]
+
+use std::sync::Arc;
+
+use opencc_rust::{
+    ConvertAsyncReader, ConvertAsyncWriter, DefaultConfig, OpenCC, Punctuation, PunctuationStyle,
+};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+#[tokio::test]
+async fn convert_async_reader() {
+    let opencc = Arc::new(OpenCC::new(DefaultConfig::S2TWP).unwrap());
+
+    let input = "凉风有讯，秋月无边。\n".repeat(2000);
+
+    let mut reader = ConvertAsyncReader::new(opencc, input.as_bytes());
+
+    let mut output = String::new();
+    reader.read_to_string(&mut output).await.unwrap();
+
+    assert_eq!("涼風有訊，秋月無邊。\n".repeat(2000), output);
+}
+
+#[tokio::test]
+async fn convert_async_writer() {
+    let opencc = Arc::new(OpenCC::new(DefaultConfig::S2TWP).unwrap());
+
+    let input = "凉风有讯，秋月无边。\n".repeat(2000);
+
+    let mut writer = ConvertAsyncWriter::new(opencc, Vec::new());
+
+    // the chunks split characters
+    for chunk in input.as_bytes().chunks(7) {
+        writer.write_all(chunk).await.unwrap();
+    }
+
+    writer.shutdown().await.unwrap();
+
+    assert_eq!("涼風有訊，秋月無邊。\n".repeat(2000).into_bytes(), writer.into_inner());
+}
+
+#[tokio::test]
+async fn convert_async() {
+    let opencc = Arc::new(OpenCC::new(DefaultConfig::S2TWP).unwrap());
+
+    assert_eq!("涼風有訊", opencc.convert_async("凉风有讯").await);
+}
+
+#[tokio::test]
+async fn convert_async_stages() {
+    let mut opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    opencc.set_punctuation(Some(Punctuation::new(PunctuationStyle::Taiwan)));
+
+    let opencc = Arc::new(opencc);
+
+    // the quotation spans many pieces
+    let input = format!("“{}”", "凉风有讯，秋月无边。\n".repeat(2000));
+    let expected = format!("「{}」", "涼風有訊，秋月無邊。\n".repeat(2000));
+
+    assert_eq!(expected, opencc.convert(&input));
+
+    let mut reader = ConvertAsyncReader::new(opencc.clone(), input.as_bytes());
+
+    let mut output = String::new();
+    reader.read_to_string(&mut output).await.unwrap();
+
+    assert_eq!(expected, output);
+
+    let mut writer = ConvertAsyncWriter::new(opencc, Vec::new());
+
+    writer.write_all(input.as_bytes()).await.unwrap();
+    writer.shutdown().await.unwrap();
+
+    assert_eq!(expected.into_bytes(), writer.into_inner());
+}
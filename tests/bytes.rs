@@ -0,0 +1,42 @@
+#![cfg(feature = "bytes")]
+
+use opencc_rust::{ConvertBytesError, DefaultConfig, InvalidUtf8Policy, OpenCC};
+
+#[test]
+fn convert_bytes_pass_through() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    let mut input = b"\xFF".to_vec();
+    input.extend_from_slice("凉风有讯".as_bytes());
+    input.extend_from_slice(b"\x00\xC3\x28");
+    input.extend_from_slice("秋月无边".as_bytes());
+    input.extend_from_slice(b"\xE5\x87");
+
+    let mut expected = b"\xFF".to_vec();
+    expected.extend_from_slice("涼風有訊".as_bytes());
+    expected.extend_from_slice(b"\x00\xC3\x28");
+    expected.extend_from_slice("秋月無邊".as_bytes());
+    expected.extend_from_slice(b"\xE5\x87");
+
+    assert_eq!(expected, opencc.convert_bytes(&input, InvalidUtf8Policy::PassThrough).unwrap());
+}
+
+#[test]
+fn convert_bytes_report() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "涼風有訊".as_bytes(),
+        opencc.convert_bytes("凉风有讯", InvalidUtf8Policy::Report).unwrap().as_slice()
+    );
+
+    let mut input = "凉风".as_bytes().to_vec();
+    input.extend_from_slice(b"\xFF\xC3\x28");
+    input.extend_from_slice("有讯".as_bytes());
+    input.extend_from_slice(b"\xE5\x87");
+
+    let error = opencc.convert_bytes(&input, InvalidUtf8Policy::Report).unwrap_err();
+
+    assert_eq!(ConvertBytesError::InvalidInput(vec![6..7, 7..8, 15..17]), error);
+    assert_eq!("invalid UTF-8 at bytes 6..7, 7..8, 15..17", error.to_string());
+}
@@ -0,0 +1,28 @@
+#![cfg(feature = "cache")]
+
+use opencc_rust::{CacheOptions, CachedOpenCC, DefaultConfig, OpenCC};
+
+#[test]
+fn cached_opencc() {
+    let mut opencc = CachedOpenCC::new(OpenCC::new(DefaultConfig::S2TWP).unwrap(), CacheOptions {
+        capacity:       2,
+        max_key_length: 12,
+    });
+
+    assert_eq!("涼風有訊", opencc.convert("凉风有讯"));
+    assert_eq!("涼風有訊", opencc.convert("凉风有讯"));
+    assert_eq!("秋月無邊", opencc.convert("秋月无边"));
+    assert_eq!("涼風有訊，秋月無邊", opencc.convert("凉风有讯，秋月无边"));
+
+    let metrics = opencc.metrics();
+
+    assert_eq!((1, 2, 1, 2), (metrics.hits, metrics.misses, metrics.bypasses, metrics.len));
+
+    opencc.clear();
+
+    assert_eq!(0, opencc.metrics().len);
+
+    opencc.replace(OpenCC::new(DefaultConfig::TW2SP).unwrap());
+
+    assert_eq!("凉风有讯", opencc.convert("涼風有訊"));
+}
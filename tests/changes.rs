@@ -0,0 +1,56 @@
+#![cfg(feature = "diff")]
+
+use opencc_rust::{find_changes, Change, DefaultConfig, OpenCC};
+
+#[test]
+fn changes() {
+    let changes = find_changes("软件和信息\n不变\n", "軟體和資訊\n不变\n");
+
+    assert_eq!(
+        vec![
+            Change {
+                source_range: 0..6,
+                target_range: 0..6,
+                from:         "软件".to_string(),
+                to:           "軟體".to_string(),
+            },
+            Change {
+                source_range: 9..15,
+                target_range: 9..15,
+                from:         "信息".to_string(),
+                to:           "資訊".to_string(),
+            },
+        ],
+        changes
+    );
+
+    let changes = find_changes("打印机。", "印表機。");
+
+    assert_eq!(
+        vec![(0..9, 0..9, "打印机", "印表機")],
+        changes
+            .iter()
+            .map(|change| (
+                change.source_range.clone(),
+                change.target_range.clone(),
+                change.from.as_str(),
+                change.to.as_str()
+            ))
+            .collect::<Vec<_>>()
+    );
+
+    assert!(find_changes("不变", "不变").is_empty());
+}
+
+#[test]
+fn convert_with_changes() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    let (converted, changes) = opencc.convert_with_changes("凉风有讯");
+
+    assert_eq!("涼風有訊", converted);
+    assert_eq!(
+        vec!["凉风", "讯"],
+        changes.iter().map(|change| change.from.as_str()).collect::<Vec<_>>()
+    );
+}
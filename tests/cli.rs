@@ -0,0 +1,125 @@
+#![cfg(feature = "cli")]
+
+use std::{
+    env, fs,
+    io::{Read, Write},
+    path::Path,
+    process::Command,
+};
+
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+fn write_epub(path: &Path) {
+    let mut writer = ZipWriter::new(fs::File::create(path).unwrap());
+
+    writer.start_file("mimetype", SimpleFileOptions::default()).unwrap();
+    writer.write_all(b"application/epub+zip").unwrap();
+    writer.start_file("OEBPS/content.opf", SimpleFileOptions::default()).unwrap();
+    writer
+        .write_all(
+            "<metadata><dc:title>凉风有讯</dc:title><dc:language>zh-CN</dc:language></metadata>"
+                .as_bytes(),
+        )
+        .unwrap();
+    writer.finish().unwrap();
+}
+
+fn read_opf(path: &Path) -> String {
+    let mut archive = ZipArchive::new(fs::File::open(path).unwrap()).unwrap();
+    let mut opf = String::new();
+
+    archive.by_name("OEBPS/content.opf").unwrap().read_to_string(&mut opf).unwrap();
+
+    opf
+}
+
+#[test]
+fn cli_epub_language() {
+    let root = env::temp_dir().join("opencc-rust-cli-epub-language");
+    let input = root.join("input.epub");
+    let output = root.join("output.epub");
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(&root).unwrap();
+
+    write_epub(&input);
+
+    let status = Command::new(env!("CARGO_BIN_EXE_opencc-rust"))
+        .args(["-c", "s2twp", "-o"])
+        .arg(&output)
+        .arg(&input)
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+
+    assert_eq!(
+        "<metadata><dc:title>涼風有訊</dc:title><dc:language>zh-TW</dc:language></metadata>",
+        read_opf(&output)
+    );
+}
+
+#[test]
+fn cli_epub_in_place() {
+    let root = env::temp_dir().join("opencc-rust-cli-epub-in-place");
+    let book = root.join("book.epub");
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(&root).unwrap();
+
+    write_epub(&book);
+
+    let status = Command::new(env!("CARGO_BIN_EXE_opencc-rust"))
+        .args(["-c", "s2twp", "-o"])
+        .arg(&book)
+        .arg(&book)
+        .status()
+        .unwrap();
+
+    assert!(status.success());
+
+    assert_eq!(
+        "<metadata><dc:title>涼風有訊</dc:title><dc:language>zh-TW</dc:language></metadata>",
+        read_opf(&book)
+    );
+
+    // no temporary file is left
+    assert_eq!(1, fs::read_dir(&root).unwrap().count());
+}
+
+#[test]
+fn cli_file_dry_run() {
+    let root = env::temp_dir().join("opencc-rust-cli-file-dry-run");
+    let file = root.join("file.txt");
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(&root).unwrap();
+
+    fs::write(&file, "凉风有讯").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_opencc-rust"))
+        .args(["-c", "s2twp", "--in-place", "--dry-run"])
+        .arg(&file)
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(format!("changed: {}\n", file.display()), String::from_utf8(output.stdout).unwrap());
+    assert_eq!("凉风有讯", fs::read_to_string(&file).unwrap());
+
+    let output = Command::new(env!("CARGO_BIN_EXE_opencc-rust"))
+        .args(["-c", "s2twp", "--in-place", "--convert-names"])
+        .arg(&file)
+        .output()
+        .unwrap();
+
+    assert!(!output.status.success());
+    assert_eq!(
+        "--convert-names can only be used with a directory.\n",
+        String::from_utf8(output.stderr).unwrap()
+    );
+    assert_eq!("凉风有讯", fs::read_to_string(&file).unwrap());
+}
@@ -0,0 +1,91 @@
+use opencc_rust::{
+    convert_csv, CsvColumn, CsvOptions, DefaultConfig, OpenCC, Width, WidthNormalization,
+};
+
+#[test]
+fn csv_selected_columns() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    let mut output = Vec::new();
+
+    convert_csv(
+        &opencc,
+        "sku,name,description\r\n凉风-1,\"凉风有讯, \"\"秋月\"\"\",\"凉风有讯\r\n秋月无边\"\r\n"
+            .as_bytes(),
+        &mut output,
+        &CsvOptions {
+            columns: vec![CsvColumn::Name("name".to_string()), CsvColumn::Index(2)],
+            ..CsvOptions::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!(
+        "sku,name,description\r\n凉风-1,\"涼風有訊, \"\"秋月\"\"\",\"涼風有訊\r\n秋月無邊\"\r\n",
+        String::from_utf8(output).unwrap()
+    );
+}
+
+#[test]
+fn tsv_without_header() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    let mut output = Vec::new();
+
+    convert_csv(&opencc, "凉风\t秋月无边\n".as_bytes(), &mut output, &CsvOptions {
+        delimiter:  b'\t',
+        has_header: false,
+        columns:    vec![CsvColumn::Index(1)],
+    })
+    .unwrap();
+
+    assert_eq!("凉风\t秋月無邊\n", String::from_utf8(output).unwrap());
+
+    assert!(convert_csv(&opencc, "凉风\n".as_bytes(), Vec::new(), &CsvOptions {
+        has_header: false,
+        columns: vec![CsvColumn::Name("name".to_string())],
+        ..CsvOptions::default()
+    })
+    .is_err());
+}
+
+#[test]
+fn csv_stray_quotes() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    let mut output = Vec::new();
+
+    convert_csv(
+        &opencc,
+        "name,size\n凉风 5\" x,1\n秋月无边,2\n".as_bytes(),
+        &mut output,
+        &CsvOptions {
+            columns: vec![CsvColumn::Name("name".to_string())],
+            ..CsvOptions::default()
+        },
+    )
+    .unwrap();
+
+    assert_eq!("name,size\n涼風 5\" x,1\n秋月無邊,2\n", String::from_utf8(output).unwrap());
+}
+
+#[test]
+fn csv_width_normalization() {
+    let mut opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    opencc.set_width_normalization(Some(WidthNormalization {
+        punctuation: Some(Width::Half),
+        space: Some(Width::Full),
+        ..WidthNormalization::new()
+    }));
+
+    let mut output = Vec::new();
+
+    convert_csv(&opencc, "凉风，有讯,秋月 无边\n".as_bytes(), &mut output, &CsvOptions {
+        has_header: false,
+        ..CsvOptions::default()
+    })
+    .unwrap();
+
+    assert_eq!("涼風，有訊,秋月 無邊\n", String::from_utf8(output).unwrap());
+}
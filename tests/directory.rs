@@ -0,0 +1,179 @@
+#![cfg(feature = "directory")]
+
+use std::{env, fs, path::PathBuf};
+
+use opencc_rust::{convert_directory, DefaultConfig, DirectoryOptions, OpenCC};
+
+#[test]
+fn convert_directory_to_mirror() {
+    let root = env::temp_dir().join("opencc-rust-convert-directory");
+    let input = root.join("input");
+    let output = root.join("output");
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(input.join("docs")).unwrap();
+    fs::create_dir_all(input.join("vendor")).unwrap();
+
+    fs::write(input.join("docs/a.md"), "凉风有讯").unwrap();
+    fs::write(input.join("docs/b.md"), "hello").unwrap();
+    fs::write(input.join("docs/c.txt"), "秋月无边").unwrap();
+    fs::write(input.join("docs/d.md"), b"\0\x01\x02").unwrap();
+    fs::write(input.join("vendor/e.md"), "秋月无边").unwrap();
+
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    let summary = convert_directory(&opencc, &input, &DirectoryOptions {
+        include: vec!["*.md".to_string()],
+        exclude: vec!["vendor/**".to_string()],
+        output: Some(output.clone()),
+        ..DirectoryOptions::default()
+    })
+    .unwrap();
+
+    assert_eq!(vec![PathBuf::from("docs/a.md")], summary.changed);
+    assert_eq!(vec![PathBuf::from("docs/b.md")], summary.unchanged);
+    assert_eq!(vec![PathBuf::from("docs/d.md")], summary.binary);
+
+    assert_eq!("涼風有訊", fs::read_to_string(output.join("docs/a.md")).unwrap());
+    assert_eq!("hello", fs::read_to_string(output.join("docs/b.md")).unwrap());
+    assert!(!output.join("docs/c.txt").exists());
+    assert_eq!(b"\0\x01\x02", fs::read(output.join("docs/d.md")).unwrap().as_slice());
+    assert!(!output.join("vendor/e.md").exists());
+
+    assert_eq!("凉风有讯", fs::read_to_string(input.join("docs/a.md")).unwrap());
+}
+
+#[test]
+fn convert_directory_in_place() {
+    let root = env::temp_dir().join("opencc-rust-convert-directory-in-place");
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(&root).unwrap();
+
+    fs::write(root.join("a.txt"), "涼風有訊").unwrap();
+
+    let opencc = OpenCC::new(DefaultConfig::TW2SP).unwrap();
+
+    let summary = convert_directory(&opencc, &root, &DirectoryOptions::default()).unwrap();
+
+    assert_eq!(vec![PathBuf::from("a.txt")], summary.changed);
+    assert_eq!("凉风有讯", fs::read_to_string(root.join("a.txt")).unwrap());
+}
+
+#[test]
+fn convert_directory_names() {
+    let root = env::temp_dir().join("opencc-rust-convert-directory-names");
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(root.join("涼風")).unwrap();
+    // a directory without matched files is not renamed
+    fs::create_dir_all(root.join("無邊")).unwrap();
+
+    fs::write(root.join("涼風/有訊.txt"), "秋月無邊").unwrap();
+    fs::write(root.join("a.bin"), b"\0").unwrap();
+
+    let opencc = OpenCC::new(DefaultConfig::TW2SP).unwrap();
+
+    let options = DirectoryOptions {
+        convert_names: true,
+        dry_run: true,
+        ..DirectoryOptions::default()
+    };
+
+    let summary = convert_directory(&opencc, &root, &options).unwrap();
+
+    assert_eq!(
+        vec![
+            (PathBuf::from("涼風"), PathBuf::from("凉风")),
+            (PathBuf::from("涼風/有訊.txt"), PathBuf::from("凉风/有讯.txt")),
+        ],
+        summary.renamed
+    );
+    assert!(summary.collisions.is_empty());
+    assert!(root.join("涼風/有訊.txt").exists());
+
+    let summary = convert_directory(&opencc, &root, &DirectoryOptions {
+        dry_run: false,
+        ..options
+    })
+    .unwrap();
+
+    assert_eq!(vec![PathBuf::from("a.bin")], summary.binary);
+    assert_eq!("秋月无边", fs::read_to_string(root.join("凉风/有讯.txt")).unwrap());
+    assert!(!root.join("涼風").exists());
+    assert!(root.join("無邊").exists());
+}
+
+#[test]
+fn convert_directory_name_collisions() {
+    let root = env::temp_dir().join("opencc-rust-convert-directory-name-collisions");
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(&root).unwrap();
+
+    fs::write(root.join("涼風.txt"), "").unwrap();
+    fs::write(root.join("凉风.txt"), "").unwrap();
+
+    let opencc = OpenCC::new(DefaultConfig::TW2SP).unwrap();
+
+    let options = DirectoryOptions {
+        convert_names: true,
+        dry_run: true,
+        ..DirectoryOptions::default()
+    };
+
+    let summary = convert_directory(&opencc, &root, &options).unwrap();
+
+    assert_eq!(1, summary.collisions.len());
+    assert_eq!(PathBuf::from("凉风.txt"), summary.collisions[0].target);
+    assert_eq!(2, summary.collisions[0].sources.len());
+
+    assert!(convert_directory(&opencc, &root, &DirectoryOptions {
+        dry_run: false,
+        ..options
+    })
+    .is_err());
+    assert!(root.join("涼風.txt").exists());
+}
+
+#[test]
+fn convert_directory_chained_names() {
+    let root = env::temp_dir().join("opencc-rust-convert-directory-chained-names");
+    let input = root.join("input");
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(&input).unwrap();
+
+    // a config which converts `甲` to `乙` and `乙` to `丙`
+    fs::write(root.join("chain.txt"), "甲\t乙\n乙\t丙\n").unwrap();
+    fs::write(
+        root.join("chain.json"),
+        r#"{
+            "name": "chain",
+            "segmentation": { "type": "mmseg", "dict": { "type": "text", "file": "chain.txt" } },
+            "conversion_chain": [{ "dict": { "type": "text", "file": "chain.txt" } }]
+        }"#,
+    )
+    .unwrap();
+
+    fs::write(input.join("甲.txt"), "a").unwrap();
+    fs::write(input.join("乙.txt"), "b").unwrap();
+
+    let opencc = OpenCC::new(root.join("chain.json")).unwrap();
+
+    let summary = convert_directory(&opencc, &input, &DirectoryOptions {
+        convert_names: true,
+        ..DirectoryOptions::default()
+    })
+    .unwrap();
+
+    assert!(summary.collisions.is_empty());
+    assert_eq!("a", fs::read_to_string(input.join("乙.txt")).unwrap());
+    assert_eq!("b", fs::read_to_string(input.join("丙.txt")).unwrap());
+    assert!(!input.join("甲.txt").exists());
+}
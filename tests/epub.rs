@@ -0,0 +1,79 @@
+#![cfg(feature = "epub")]
+
+use std::{
+    env, fs,
+    io::{Read, Write},
+};
+
+use opencc_rust::{convert_epub, DefaultConfig, EpubOptions, OpenCC};
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+#[test]
+fn epub() {
+    let root = env::temp_dir().join("opencc-rust-convert-epub");
+    let input = root.join("input.epub");
+    let output = root.join("output.epub");
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(&root).unwrap();
+
+    let entries = [
+        ("META-INF/container.xml", "<container/>"),
+        ("mimetype", "application/epub+zip"),
+        (
+            "OEBPS/content.opf",
+            "<metadata><dc:title>凉风有讯</dc:title><dc:creator>凉风</dc:creator><dc:\
+             description>秋月无边</dc:description><dc:language>zh-CN</dc:language></metadata>",
+        ),
+        ("OEBPS/toc.ncx", "<navMap><navLabel><text>凉风有讯</text></navLabel></navMap>"),
+        ("OEBPS/chapter.xhtml", "<p>凉风<b>有讯</b></p><code>凉风</code>"),
+        ("OEBPS/style.css", "p::after { content: \"凉风\"; }"),
+    ];
+
+    let mut writer = ZipWriter::new(fs::File::create(&input).unwrap());
+
+    for (name, content) in entries.iter() {
+        writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+    }
+
+    writer.finish().unwrap();
+
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    convert_epub(&opencc, &input, &output, &EpubOptions {
+        language: Some("zh-TW".to_string())
+    })
+    .unwrap();
+
+    let mut archive = ZipArchive::new(fs::File::open(&output).unwrap()).unwrap();
+
+    let expected = [
+        ("mimetype", "application/epub+zip"),
+        ("META-INF/container.xml", "<container/>"),
+        (
+            "OEBPS/content.opf",
+            "<metadata><dc:title>涼風有訊</dc:title><dc:creator>凉风</dc:creator><dc:\
+             description>秋月無邊</dc:description><dc:language>zh-TW</dc:language></metadata>",
+        ),
+        ("OEBPS/toc.ncx", "<navMap><navLabel><text>涼風有訊</text></navLabel></navMap>"),
+        ("OEBPS/chapter.xhtml", "<p>涼風<b>有訊</b></p><code>凉风</code>"),
+        ("OEBPS/style.css", "p::after { content: \"凉风\"; }"),
+    ];
+
+    for (index, (name, content)) in expected.iter().enumerate() {
+        let mut file = archive.by_index(index).unwrap();
+
+        assert_eq!(*name, file.name());
+
+        if index == 0 {
+            assert_eq!(CompressionMethod::Stored, file.compression());
+        }
+
+        let mut s = String::new();
+        file.read_to_string(&mut s).unwrap();
+
+        assert_eq!(*content, s);
+    }
+}
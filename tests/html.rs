@@ -0,0 +1,43 @@
+use opencc_rust::{convert_html, DefaultConfig, OpenCC};
+
+#[test]
+fn html_text_and_attributes() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "<p title=\"涼風有訊\" class=\"凉风有讯\">涼風有訊</p><img alt='秋月無邊' \
+         src=\"凉风.png\"><meta name=\"description\" content=\"秋月無邊\">",
+        convert_html(
+            &opencc,
+            "<p title=\"凉风有讯\" class=\"凉风有讯\">凉风有讯</p><img alt='秋月无边' \
+             src=\"凉风.png\"><meta name=\"description\" content=\"秋月无边\">"
+        )
+    );
+}
+
+#[test]
+fn html_skipped_elements() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "<script>var s = \"凉风<p>\";</script><style>p::after { content: \"凉风\"; \
+         }</style><code>凉风</code><pre>凉风</pre><div translate=\"no\">凉风<span \
+         translate=\"yes\">涼風</span></div>",
+        convert_html(
+            &opencc,
+            "<script>var s = \"凉风<p>\";</script><style>p::after { content: \"凉风\"; \
+             }</style><code>凉风</code><pre>凉风</pre><div translate=\"no\">凉风<span \
+             translate=\"yes\">凉风</span></div>"
+        )
+    );
+}
+
+#[test]
+fn html_phrase_across_inline_elements() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "<p><b>軟</b>體 &amp; 涼風</p>",
+        convert_html(&opencc, "<p><b>软</b>件 &amp; 凉风</p>")
+    );
+}
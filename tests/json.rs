@@ -0,0 +1,88 @@
+#![cfg(feature = "json")]
+
+use opencc_rust::{convert_json, convert_json_value, DefaultConfig, JsonOptions, OpenCC};
+
+#[test]
+fn json_values() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "{\n  \"凉风\": \"涼風有訊\",\n  \"list\": [\"秋月無邊\", 1, \"\\u6dbc\\u98a8\"]\n}",
+        convert_json(
+            &opencc,
+            "{\n  \"凉风\": \"凉风有讯\",\n  \"list\": [\"秋月无边\", 1, \"\\u51c9\\u98ce\"]\n}",
+            &JsonOptions::default()
+        )
+        .unwrap()
+    );
+}
+
+#[test]
+fn json_keys_and_rules() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    let options = JsonOptions {
+        convert_keys: true,
+        include:      vec!["$.menu".to_string()],
+        exclude:      vec!["$..id".to_string()],
+    };
+
+    assert_eq!(
+        "{\"menu\": [{\"涼風\": \"涼風\", \"id\": \"凉风\"}], \"凉风\": \"凉风\"}",
+        convert_json(
+            &opencc,
+            "{\"menu\": [{\"凉风\": \"凉风\", \"id\": \"凉风\"}], \"凉风\": \"凉风\"}",
+            &options
+        )
+        .unwrap()
+    );
+
+    assert!(convert_json(&opencc, "{", &JsonOptions::default()).is_err());
+}
+
+#[test]
+fn json_value() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    let mut value = serde_json::json!({ "a": ["凉风有讯", { "b": "秋月无边" }] });
+
+    convert_json_value(&opencc, &mut value, &JsonOptions {
+        exclude: vec!["$.a[1]".to_string()],
+        ..JsonOptions::default()
+    })
+    .unwrap();
+
+    assert_eq!(serde_json::json!({ "a": ["涼風有訊", { "b": "秋月无边" }] }), value);
+}
+
+#[test]
+fn json_key_collisions() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    let options = JsonOptions {
+        convert_keys: true,
+        ..JsonOptions::default()
+    };
+
+    assert!(convert_json(&opencc, r#"{"凉风": 1, "涼風": 2}"#, &options).is_err());
+    assert_eq!(
+        r#"{"凉风": 1, "涼風": 2}"#,
+        convert_json(&opencc, r#"{"凉风": 1, "涼風": 2}"#, &JsonOptions::default()).unwrap()
+    );
+
+    let mut value = serde_json::json!({ "a": "凉风", "b": { "凉风": 1, "涼風": 2 } });
+
+    assert!(convert_json_value(&opencc, &mut value, &options).is_err());
+    assert_eq!(serde_json::json!({ "a": "凉风", "b": { "凉风": 1, "涼風": 2 } }), value);
+}
+
+#[test]
+fn json_nul() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "[\"\\u0000\\u6dbc\\u98a8\", \"涼風\"]",
+        convert_json(&opencc, "[\"\\u0000凉风\", \"凉风\"]", &JsonOptions::default()).unwrap()
+    );
+    assert_eq!("涼風\0有訊", opencc.convert("凉风\0有讯"));
+}
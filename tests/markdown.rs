@@ -0,0 +1,48 @@
+#![cfg(feature = "markdown")]
+
+use opencc_rust::{convert_markdown, DefaultConfig, OpenCC};
+
+#[test]
+fn markdown_prose() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "# 涼風有訊\n\n*秋月*無邊，[涼風](https://example.com/凉风 \"凉风\")。\n\n| 涼風 | 有訊 \
+         |\n|---|---|\n| 秋月 | 無邊 |\n",
+        convert_markdown(
+            &opencc,
+            "# 凉风有讯\n\n*秋月*无边，[凉风](https://example.com/凉风 \"凉风\")。\n\n| 凉风 | \
+             有讯 |\n|---|---|\n| 秋月 | 无边 |\n"
+        )
+    );
+}
+
+#[test]
+fn markdown_code_and_labels() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    let markdown = "`凉风` <https://example.com/凉风>\n\n```\n凉风\n```\n\n<div>凉风</div>\n\n[凉风][]\n\n[凉风]: https://example.com\n";
+
+    assert_eq!(markdown, convert_markdown(&opencc, markdown));
+}
+
+#[test]
+fn markdown_phrase_across_emphasis() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!("**軟**體\n", convert_markdown(&opencc, "**软**件\n"));
+}
+
+#[test]
+fn markdown_front_matter() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "---\ntitle: 涼風\ntags: [秋月, 無邊]\n---\n\n涼風\n",
+        convert_markdown(&opencc, "---\ntitle: 凉风\ntags: [秋月, 无边]\n---\n\n凉风\n")
+    );
+    assert_eq!(
+        "+++\ntitle = \"涼風\"\n+++\n\n涼風\n",
+        convert_markdown(&opencc, "+++\ntitle = \"凉风\"\n+++\n\n凉风\n")
+    );
+}
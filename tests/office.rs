@@ -0,0 +1,96 @@
+#![cfg(feature = "office")]
+
+use std::{
+    env, fs,
+    io::{Read, Write},
+    path::Path,
+};
+
+use opencc_rust::{convert_docx, convert_odt, DefaultConfig, OpenCC};
+use zip::{write::SimpleFileOptions, ZipArchive, ZipWriter};
+
+fn write_zip(path: &Path, entries: &[(&str, &str)]) {
+    let mut writer = ZipWriter::new(fs::File::create(path).unwrap());
+
+    for (name, content) in entries.iter() {
+        writer.start_file(*name, SimpleFileOptions::default()).unwrap();
+        writer.write_all(content.as_bytes()).unwrap();
+    }
+
+    writer.finish().unwrap();
+}
+
+fn read_zip_entry(path: &Path, name: &str) -> String {
+    let mut archive = ZipArchive::new(fs::File::open(path).unwrap()).unwrap();
+
+    let mut s = String::new();
+    archive.by_name(name).unwrap().read_to_string(&mut s).unwrap();
+
+    s
+}
+
+#[test]
+fn docx() {
+    let root = env::temp_dir().join("opencc-rust-convert-docx");
+    let input = root.join("input.docx");
+    let output = root.join("output.docx");
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(&root).unwrap();
+
+    write_zip(&input, &[
+        (
+            "word/document.xml",
+            "<w:body><w:p><w:r><w:rPr><w:b/></w:rPr><w:t>凉风</w:t></w:r><w:r><w:t>有讯</w:t></w:\
+             r><w:del><w:r><w:delText>凉风</w:delText></w:r></w:del></w:p></w:body>",
+        ),
+        ("word/styles.xml", "<w:styles><w:name w:val=\"凉风\"/></w:styles>"),
+    ]);
+
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    convert_docx(&opencc, &input, &output).unwrap();
+
+    assert_eq!(
+        "<w:body><w:p><w:r><w:rPr><w:b/></w:rPr><w:t>涼風</w:t></w:r><w:r><w:t>有訊</w:t></w:r><w:\
+         del><w:r><w:delText>凉风</w:delText></w:r></w:del></w:p></w:body>",
+        read_zip_entry(&output, "word/document.xml")
+    );
+    assert_eq!(
+        "<w:styles><w:name w:val=\"凉风\"/></w:styles>",
+        read_zip_entry(&output, "word/styles.xml")
+    );
+}
+
+#[test]
+fn odt() {
+    let root = env::temp_dir().join("opencc-rust-convert-odt");
+    let input = root.join("input.odt");
+    let output = root.join("output.odt");
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(&root).unwrap();
+
+    write_zip(&input, &[
+        ("mimetype", "application/vnd.oasis.opendocument.text"),
+        (
+            "content.xml",
+            "<office:text><text:p>凉风<text:span \
+             text:style-name=\"T1\">有讯</text:span><office:annotation><text:p>凉风</text:p></\
+             office:annotation></text:p></office:text>",
+        ),
+    ]);
+
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    convert_odt(&opencc, &input, &output).unwrap();
+
+    assert_eq!(
+        "<office:text><text:p>涼風<text:span \
+         text:style-name=\"T1\">有訊</text:span><office:annotation><text:p>凉风</text:p></office:\
+         annotation></text:p></office:text>",
+        read_zip_entry(&output, "content.xml")
+    );
+}
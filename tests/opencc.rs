@@ -55,3 +55,16 @@ fn s2twp_to_buffer() {
         &s
     );
 }
+
+#[test]
+fn s2twp_cow() {
+    use std::borrow::Cow;
+
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert!(matches!(opencc.convert_cow("Hello, world!"), Cow::Borrowed("Hello, world!")));
+    assert!(matches!(opencc.convert_cow("涼風有訊"), Cow::Borrowed("涼風有訊")));
+    assert!(matches!(opencc.convert_cow("凉风有讯"), Cow::Owned(s) if s == "涼風有訊"));
+    // the characters are unchanged on their own, but not as a phrase
+    assert!(matches!(opencc.convert_cow("信息"), Cow::Owned(s) if s == "資訊"));
+}
@@ -0,0 +1,27 @@
+#![cfg(feature = "rayon")]
+
+use opencc_rust::{DefaultConfig, OpenCC, Punctuation, PunctuationStyle};
+
+#[test]
+fn convert_batch() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        vec!["涼風有訊".to_string(), "秋月無邊".to_string()],
+        opencc.convert_batch(&["凉风有讯", "秋月无边"])
+    );
+}
+
+#[test]
+fn par_convert() {
+    let mut opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    opencc.set_punctuation(Some(Punctuation::new(PunctuationStyle::Taiwan)));
+
+    let input = format!("“{}”", "凉风有讯，秋月无边。\n".repeat(20000));
+
+    let converted = opencc.par_convert(&input);
+
+    assert_eq!(format!("「{}」", "涼風有訊，秋月無邊。\n".repeat(20000)), converted);
+    assert_eq!(opencc.convert(&input), converted);
+}
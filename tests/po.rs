@@ -0,0 +1,51 @@
+use opencc_rust::{convert_po, DefaultConfig, OpenCC, PoOptions};
+
+#[test]
+fn po() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    let options = PoOptions {
+        convert_translator_comments: true,
+        language:                    Some("zh_TW".to_string()),
+        flag:                        Some("fuzzy".to_string()),
+    };
+
+    assert_eq!(
+        "msgid \"\"\nmsgstr \"\"\n\"Language: zh_TW\\n\"\n\n# 涼風有訊\n#, c-format, \
+         fuzzy\nmsgctxt \"凉风\"\nmsgid \"凉风有讯 %s\"\nmsgstr \"涼風\"\n\"有訊 %s\"\n\n#: \
+         main.c:1\n#, fuzzy\nmsgid \"秋月无边\"\nmsgstr \"秋月無邊\"\n\nmsgid \"%d\"\nmsgstr \
+         \"%d\"\n",
+        convert_po(
+            &opencc,
+            "msgid \"\"\nmsgstr \"\"\n\"Language: zh_CN\\n\"\n\n# 凉风有讯\n#, c-format\nmsgctxt \
+             \"凉风\"\nmsgid \"凉风有讯 %s\"\nmsgstr \"凉风\"\n\"有讯 %s\"\n\n#: main.c:1\nmsgid \
+             \"秋月无边\"\nmsgstr \"秋月无边\"\n\nmsgid \"%d\"\nmsgstr \"%d\"\n",
+            &options
+        )
+    );
+}
+
+#[test]
+fn po_missing_language() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    let options = PoOptions {
+        language: Some("zh_TW".to_string()),
+        ..PoOptions::default()
+    };
+
+    assert_eq!(
+        "msgid \"\"\nmsgstr \"\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n\"Language: \
+         zh_TW\\n\"\n\nmsgid \"a\"\nmsgstr \"涼風\"\n",
+        convert_po(
+            &opencc,
+            "msgid \"\"\nmsgstr \"\"\n\"Content-Type: text/plain; charset=UTF-8\\n\"\n\nmsgid \
+             \"a\"\nmsgstr \"凉风\"\n",
+            &options
+        )
+    );
+    assert_eq!(
+        "msgid \"\"\r\nmsgstr \"\"\r\n\"Language: zh_TW\\n\"\r\n",
+        convert_po(&opencc, "msgid \"\"\r\nmsgstr \"\"\r\n", &options)
+    );
+}
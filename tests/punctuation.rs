@@ -0,0 +1,27 @@
+use opencc_rust::{DefaultConfig, OpenCC, Punctuation, PunctuationStyle};
+
+#[test]
+fn punctuation_nesting() {
+    let taiwan = Punctuation::new(PunctuationStyle::Taiwan);
+
+    assert_eq!("他說：「她說『不』，don’t」", taiwan.apply("他說：“她說‘不’，don’t”"));
+
+    let mainland = Punctuation::new(PunctuationStyle::Mainland);
+
+    assert_eq!("“外‘中“內”’”", mainland.apply("「外『中「內」』」"));
+}
+
+#[test]
+fn punctuation_stage() {
+    let mut opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    opencc.set_punctuation(DefaultConfig::S2TWP.get_punctuation_style().map(Punctuation::new));
+
+    assert_eq!("「涼風有訊」", opencc.convert("“凉风有讯”"));
+    // each call starts outside any quotation, and the buffer is kept as it is
+    assert_eq!(
+        "「涼風有訊」「秋月無邊」",
+        opencc.convert_to_buffer("『秋月无边』", "「涼風有訊」".to_string())
+    );
+    assert_eq!("“凉风「秋月無邊」", opencc.convert_to_buffer("『秋月无边』", "“凉风".to_string()));
+}
@@ -0,0 +1,66 @@
+use opencc_rust::{
+    convert_toml, convert_yaml, DefaultConfig, Format, OpenCC, ResourceOptions, Width,
+    WidthNormalization,
+};
+
+#[test]
+fn yaml() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    let options = ResourceOptions {
+        locale_key: Some(("zh-CN".to_string(), "zh-TW".to_string()))
+    };
+
+    assert_eq!(
+        "zh-TW:\n  凉风: &a \"涼風有訊\" # 凉风\n  b: *a\n  c: |\n    秋月無邊\n  d: [涼風有訊, \
+         '秋月無邊']\n",
+        convert_yaml(
+            &opencc,
+            "zh-CN:\n  凉风: &a \"凉风有讯\" # 凉风\n  b: *a\n  c: |\n    秋月无边\n  d: \
+             [凉风有讯, '秋月无边']\n",
+            &options
+        )
+    );
+}
+
+#[test]
+fn toml() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    let options = ResourceOptions {
+        locale_key: Some(("zh-CN".to_string(), "zh-TW".to_string()))
+    };
+
+    assert_eq!(
+        "# 凉风\n[zh-TW.menu]\ntitle = \"涼風有訊\" # 秋月无边\nitems = ['秋月無邊', { \"凉风\" = \
+         \"涼風\" }]\n",
+        convert_toml(
+            &opencc,
+            "# 凉风\n[zh-CN.menu]\ntitle = \"凉风有讯\" # 秋月无边\nitems = ['秋月无边', { \
+             \"凉风\" = \"凉风\" }]\n",
+            &options
+        )
+    );
+}
+
+#[test]
+fn format_from_path() {
+    assert_eq!(Format::Yaml, Format::from_path("config/locales/zh-CN.yml"));
+    assert_eq!(Format::Toml, Format::from_path("Config.TOML"));
+    assert_eq!(Format::Plain, Format::from_path("README"));
+}
+
+#[test]
+fn yaml_width_normalization() {
+    let mut opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    opencc.set_width_normalization(Some(WidthNormalization {
+        punctuation: Some(Width::Half),
+        ..WidthNormalization::new()
+    }));
+
+    assert_eq!(
+        "a: [涼風，有訊]\n",
+        convert_yaml(&opencc, "a: [凉风，有讯]\n", &ResourceOptions::default())
+    );
+}
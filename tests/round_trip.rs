@@ -0,0 +1,110 @@
+#![cfg(feature = "diff")]
+
+use std::{env, fs, path::Path};
+
+use opencc_rust::{round_trip_check, DefaultConfig, OpenCC, RoundTripChecker, RoundTripLossKind};
+
+/// Load a config which converts with only the given text dictionary.
+fn open_text_dictionary(root: &Path, name: &str, dictionary: &str) -> OpenCC {
+    fs::write(root.join(format!("{}.txt", name)), dictionary).unwrap();
+    fs::write(
+        root.join(format!("{}.json", name)),
+        format!(
+            r#"{{
+                "name": "{0}",
+                "segmentation": {{ "type": "mmseg", "dict": {{ "type": "text", "file": "{0}.txt" }} }},
+                "conversion_chain": [{{ "dict": {{ "type": "text", "file": "{0}.txt" }} }}]
+            }}"#,
+            name
+        ),
+    )
+    .unwrap();
+
+    OpenCC::new(root.join(format!("{}.json", name))).unwrap()
+}
+
+#[test]
+fn inverse() {
+    assert_eq!("tw2sp.json", DefaultConfig::S2TWP.inverse().get_file_name());
+    assert_eq!("s2twp.json", DefaultConfig::TW2SP.inverse().get_file_name());
+    assert_eq!("t2s.json", DefaultConfig::S2T.inverse().get_file_name());
+}
+
+#[test]
+fn round_trip() {
+    let round_trip = round_trip_check("凉风有讯，秋月无边", DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!("涼風有訊，秋月無邊", round_trip.converted);
+    assert_eq!("凉风有讯，秋月无边", round_trip.restored);
+    assert!(round_trip.is_lossless());
+}
+
+#[test]
+fn round_trip_checker() {
+    let checker = RoundTripChecker::from_opencc(
+        OpenCC::new(DefaultConfig::S2TWP).unwrap(),
+        OpenCC::new(DefaultConfig::TW2SP).unwrap(),
+    );
+
+    for (text, converted) in [("凉风有讯", "涼風有訊"), ("秋月无边", "秋月無邊")] {
+        let round_trip = checker.check(text);
+
+        assert_eq!(converted, round_trip.converted);
+        assert_eq!(text, round_trip.restored);
+        assert!(round_trip.is_lossless());
+    }
+}
+
+#[test]
+fn round_trip_character_merge() {
+    let round_trip = round_trip_check("髮", DefaultConfig::T2S).unwrap();
+
+    assert_eq!("发", round_trip.converted);
+    assert_eq!("發", round_trip.restored);
+    assert_eq!(1, round_trip.losses.len());
+
+    let loss = &round_trip.losses[0];
+
+    assert_eq!(0..3, loss.source_range);
+    assert_eq!("髮", loss.original);
+    assert_eq!("发", loss.converted);
+    assert_eq!("發", loss.restored);
+    assert_eq!(RoundTripLossKind::CharacterMerge, loss.kind);
+}
+
+#[test]
+fn round_trip_checker_losses() {
+    let root = env::temp_dir().join("opencc-rust-round-trip-checker-losses");
+
+    let _ = fs::remove_dir_all(&root);
+
+    fs::create_dir_all(&root).unwrap();
+
+    // `乾` and `幹` are merged into `干`, which goes back to `幹`, and the phrase `电脑` goes back to another form
+    let checker = RoundTripChecker::from_opencc(
+        open_text_dictionary(&root, "forward", "乾\t干\n幹\t干\n电脑\t计算机\n"),
+        open_text_dictionary(&root, "inverse", "干\t幹\n计算机\t電腦\n"),
+    );
+
+    let round_trip = checker.check("乾了，用电脑");
+
+    assert_eq!("干了，用计算机", round_trip.converted);
+    assert_eq!("幹了，用電腦", round_trip.restored);
+    assert_eq!(2, round_trip.losses.len());
+
+    let merge = &round_trip.losses[0];
+
+    assert_eq!(0..3, merge.source_range);
+    assert_eq!("乾", merge.original);
+    assert_eq!("干", merge.converted);
+    assert_eq!("幹", merge.restored);
+    assert_eq!(RoundTripLossKind::CharacterMerge, merge.kind);
+
+    let idiom = &round_trip.losses[1];
+
+    assert_eq!(12..18, idiom.source_range);
+    assert_eq!("电脑", idiom.original);
+    assert_eq!("计算机", idiom.converted);
+    assert_eq!("電腦", idiom.restored);
+    assert_eq!(RoundTripLossKind::PhraseIdiom, idiom.kind);
+}
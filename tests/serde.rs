@@ -0,0 +1,39 @@
+#![cfg(feature = "serde")]
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Product {
+    #[serde(serialize_with = "opencc_rust::serde::s2twp::serialize")]
+    name:        String,
+    #[serde(with = "opencc_rust::serde::s2twp::option")]
+    description: Option<String>,
+    #[serde(deserialize_with = "opencc_rust::serde::tw2sp::deserialize")]
+    note:        String,
+}
+
+#[test]
+fn serde_modules() {
+    let product = Product {
+        name:        "凉风有讯".to_string(),
+        description: Some("秋月无边".to_string()),
+        note:        "凉风有讯".to_string(),
+    };
+
+    let json = serde_json::to_string(&product).unwrap();
+
+    assert_eq!(r#"{"name":"涼風有訊","description":"秋月無邊","note":"凉风有讯"}"#, json);
+
+    let product: Product =
+        serde_json::from_str(r#"{"name":"涼風有訊","description":null,"note":"涼風有訊"}"#)
+            .unwrap();
+
+    assert_eq!(
+        Product {
+            name:        "涼風有訊".to_string(),
+            description: None,
+            note:        "凉风有讯".to_string(),
+        },
+        product
+    );
+}
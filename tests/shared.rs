@@ -0,0 +1,15 @@
+use std::sync::Arc;
+
+use opencc_rust::{DefaultConfig, OpenCC};
+
+#[test]
+fn shared() {
+    OpenCC::preload_shared(&[DefaultConfig::S2TWP, DefaultConfig::TW2SP]).unwrap();
+
+    let s2twp = OpenCC::shared(DefaultConfig::S2TWP).unwrap();
+
+    assert!(Arc::ptr_eq(&s2twp, &OpenCC::shared(DefaultConfig::S2TWP).unwrap()));
+    assert!(!Arc::ptr_eq(&s2twp, &OpenCC::shared(DefaultConfig::TW2SP).unwrap()));
+
+    assert_eq!("涼風有訊", s2twp.convert("凉风有讯"));
+}
@@ -0,0 +1,93 @@
+use opencc_rust::{convert_source, DefaultConfig, Format, Language, OpenCC, SourceOptions};
+
+#[test]
+fn source_comments_and_strings() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "// 涼風有訊\nfn 凉风() { let s = \"秋月無邊\"; let r = r#\"涼風\"#; let c = '凉'; } /* \
+         秋月無邊 */\n",
+        convert_source(
+            &opencc,
+            "// 凉风有讯\nfn 凉风() { let s = \"秋月无边\"; let r = r#\"凉风\"#; let c = '凉'; } \
+             /* 秋月无边 */\n",
+            Language::Rust,
+            &SourceOptions::default()
+        )
+    );
+
+    assert_eq!(
+        "const 凉风 = `涼風${ f('秋月無邊') }有訊`; // 秋月無邊\n",
+        convert_source(
+            &opencc,
+            "const 凉风 = `凉风${ f('秋月无边') }有讯`; // 秋月无边\n",
+            Language::JavaScript,
+            &SourceOptions {
+                comments_only: false
+            }
+        )
+    );
+}
+
+#[test]
+fn source_comments_only() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "# 涼風有訊\ns = '秋月无边'\n",
+        convert_source(
+            &opencc,
+            "# 凉风有讯\ns = '秋月无边'\n",
+            Language::Python,
+            &SourceOptions {
+                comments_only: true
+            }
+        )
+    );
+}
+
+#[test]
+fn source_format() {
+    assert_eq!(Format::Source(Language::JavaScript), Format::from_path("src/app.tsx"));
+    assert_eq!(Format::Source(Language::C), Format::from_path("main.cpp"));
+}
+
+#[test]
+fn source_interpolations() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "s = f\"涼風{凉风}有訊\" + rf'{凉风}\\d秋月' + f\"{{秋月無邊}}\"\n",
+        convert_source(
+            &opencc,
+            "s = f\"凉风{凉风}有讯\" + rf'{凉风}\\d秋月' + f\"{{秋月无边}}\"\n",
+            Language::Python,
+            &SourceOptions::default()
+        )
+    );
+
+    assert_eq!(
+        "let s = format!(\"涼風{凉风}有訊{凉风:>5}{{秋月無邊}}\");\n",
+        convert_source(
+            &opencc,
+            "let s = format!(\"凉风{凉风}有讯{凉风:>5}{{秋月无边}}\");\n",
+            Language::Rust,
+            &SourceOptions::default()
+        )
+    );
+}
+
+#[test]
+fn source_regex_literals() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "const re = /\"/g; const 凉风 = \"涼風\"; // 有訊\nconst x = a / b / \"秋月\";\n",
+        convert_source(
+            &opencc,
+            "const re = /\"/g; const 凉风 = \"凉风\"; // 有讯\nconst x = a / b / \"秋月\";\n",
+            Language::JavaScript,
+            &SourceOptions::default()
+        )
+    );
+}
@@ -0,0 +1,29 @@
+#![cfg(feature = "statistics")]
+
+use opencc_rust::{DefaultConfig, DictionaryProfiler};
+
+#[test]
+fn dictionary_profiler() {
+    let profiler = DictionaryProfiler::new(DefaultConfig::S2TWP).unwrap();
+
+    let (converted, statistics) = profiler.convert_with_statistics("凉风有讯，秋月无边");
+
+    assert_eq!("涼風有訊，秋月無邊", converted);
+
+    assert_eq!(
+        vec!["STPhrases.ocd2", "STCharacters.ocd2", "TWPhrases.ocd2", "TWVariants.ocd2"],
+        statistics.dictionaries.iter().map(|matches| matches.dictionary).collect::<Vec<_>>()
+    );
+    assert_eq!(8, statistics.cjk_characters);
+    assert_eq!(5, statistics.dictionaries.iter().map(|matches| matches.characters).sum::<usize>());
+    assert_eq!(3, statistics.unchanged_characters);
+    assert_eq!(Some(&1), statistics.unchanged.get(&'有'));
+
+    profiler.convert_with_statistics("有");
+
+    assert_eq!(Some(&2), profiler.statistics().unchanged.get(&'有'));
+
+    profiler.reset_statistics();
+
+    assert_eq!(0, profiler.statistics().cjk_characters);
+}
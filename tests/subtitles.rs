@@ -0,0 +1,46 @@
+use opencc_rust::{convert_ass, convert_srt, convert_vtt, DefaultConfig, OpenCC};
+
+#[test]
+fn srt() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "\u{feff}1\r\n00:00:01,000 --> 00:00:02,000\r\n{\\an8}<i>涼風有訊</i>\r\n秋月無邊\r\n",
+        convert_srt(
+            &opencc,
+            "\u{feff}1\r\n00:00:01,000 --> 00:00:02,000\r\n{\\an8}<i>凉风有讯</i>\r\n秋月无边\r\n"
+        )
+    );
+}
+
+#[test]
+fn vtt() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "WEBVTT\n\nNOTE 凉风\n\n凉风\n00:01.000 --> 00:02.000 align:start\n<v \
+         凉风>涼風有訊<00:01.500>秋月無邊</v>\n",
+        convert_vtt(
+            &opencc,
+            "WEBVTT\n\nNOTE 凉风\n\n凉风\n00:01.000 --> 00:02.000 align:start\n<v \
+             凉风>凉风有讯<00:01.500>秋月无边</v>\n"
+        )
+    );
+}
+
+#[test]
+fn ass() {
+    let opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    assert_eq!(
+        "[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, \
+         Text\nDialogue: \
+         0,0:00:01.00,0:00:02.00,Default,凉风,0,0,0,,{\\i1}涼風有訊\\N秋月無邊{\\i0}\n",
+        convert_ass(
+            &opencc,
+            "[Events]\nFormat: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, \
+             Text\nDialogue: \
+             0,0:00:01.00,0:00:02.00,Default,凉风,0,0,0,,{\\i1}凉风有讯\\N秋月无边{\\i0}\n"
+        )
+    );
+}
@@ -0,0 +1,48 @@
+#![cfg(feature = "unicode-normalization")]
+
+use opencc_rust::{DefaultConfig, NormalizedCharacter, OpenCC, UnicodeNormalization};
+
+#[test]
+fn unicode_normalization() {
+    let unicode = UnicodeNormalization::default();
+
+    assert_eq!("說言辶凉风", unicode.apply("\u{F96F}⾔⻌凉风"));
+    assert_eq!("caf\u{E9}", unicode.apply("cafe\u{301}"));
+
+    // ideographic variation selectors are kept
+    assert_eq!("葛\u{E0100}言", unicode.apply("葛\u{E0100}⾔"));
+
+    let (output, report) = unicode.apply_with_report("A\u{F96F}⾔");
+
+    assert_eq!("A說言", output);
+    assert_eq!(
+        vec![
+            NormalizedCharacter {
+                range: 1..4,
+                from:  "\u{F96F}".to_string(),
+                to:    "說".to_string(),
+            },
+            NormalizedCharacter {
+                range: 4..7, from: "⾔".to_string(), to: "言".to_string()
+            },
+        ],
+        report
+    );
+
+    let nfc_only = UnicodeNormalization {
+        compatibility_ideographs: false,
+        radicals: false,
+        ..unicode
+    };
+
+    assert_eq!("\u{F96F}⾔caf\u{E9}", nfc_only.apply("\u{F96F}⾔cafe\u{301}"));
+}
+
+#[test]
+fn unicode_normalization_stage() {
+    let mut opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    opencc.set_unicode_normalization(Some(UnicodeNormalization::default()));
+
+    assert_eq!("涼風有訊", opencc.convert("凉⻛有讯"));
+}
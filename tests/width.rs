@@ -0,0 +1,37 @@
+use opencc_rust::{DefaultConfig, OpenCC, Width, WidthNormalization};
+
+#[test]
+fn width_normalization() {
+    let width = WidthNormalization::new();
+
+    assert_eq!(
+        "ABC123，凉风！Hello, world! ガパ",
+        width.apply("ＡＢＣ１２３,凉风!Hello, world! ｶﾞﾊﾟ")
+    );
+
+    let (output, offsets) = width.apply_with_offsets("ｶﾞ１");
+
+    assert_eq!("ガ1", output);
+    assert_eq!(vec![0..6, 6..9], offsets);
+
+    let full = WidthNormalization {
+        alphanumerics: Some(Width::Full),
+        space: Some(Width::Half),
+        punctuation: Some(Width::Half),
+        ..width
+    };
+
+    assert_eq!("ａｂｃ 凉风,秋月!", full.apply("abc\u{3000}凉风，秋月！"));
+}
+
+#[test]
+fn width_normalization_stage() {
+    let mut opencc = OpenCC::new(DefaultConfig::S2TWP).unwrap();
+
+    opencc.set_width_normalization(Some(WidthNormalization {
+        before_conversion: true,
+        ..WidthNormalization::new()
+    }));
+
+    assert_eq!("涼風有訊，ABC", opencc.convert("凉风有讯,ＡＢＣ"));
+}